@@ -1,60 +1,188 @@
-pub fn process_part1(input: &str) -> String {
-    let result = input
-        .split("\n\n")
-        .map(|elf_load| {
-            elf_load
-                .lines()
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .max()
-        .unwrap();
-    result.to_string()
-}
-
-pub fn process_part2(input: &str) -> String {
-    let mut result = input
-        .split("\n\n")
-        .map(|elf_load| {
-            elf_load
-                .lines()
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .collect::<Vec<_>>();
-    result.sort_by(|a, b| b.cmp(a));
-    let sum: u32 = result.iter().take(3).sum();
-    sum.to_string()
+use aoc_common::{normalize, AocError, Result, Solution};
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    io::{BufRead, Lines},
+};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 1;
+    const EXAMPLE: &'static str = include_str!("../examples/01-a.txt");
+
+    /// Each elf's total, in the order the elves are listed.
+    type Parsed<'a> = Vec<u64>;
+    type Params = ();
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        elf_totals(input.as_bytes()).collect()
+    }
+
+    fn part1(totals: &Self::Parsed<'_>, _params: &()) -> Result<u64> {
+        rank(totals.iter().copied(), 1)
+            .first()
+            .map(|top| top.calories)
+            .ok_or_else(|| AocError::unsolvable("there are no elves"))
+    }
+
+    fn part2(totals: &Self::Parsed<'_>, _params: &()) -> Result<u64> {
+        Ok(rank(totals.iter().copied(), 3)
+            .iter()
+            .map(|ranked| ranked.calories)
+            .sum())
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// An elf, counted from 0 in input order, and the calories they carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ranked {
+    pub elf: usize,
+    pub calories: u64,
+}
 
-    const INPUT: &str = "1000
-3000
-2000
+/// Sums each elf's load as lines are read, so only one line is held at a time.
+pub struct ElfTotals<R> {
+    lines: Lines<R>,
+    line: usize,
+}
+
+/// Each elf's total from `reader`, with loads separated by blank lines.
+pub fn elf_totals<R: BufRead>(reader: R) -> ElfTotals<R> {
+    ElfTotals {
+        lines: reader.lines(),
+        line: 0,
+    }
+}
 
-4000
+impl<R: BufRead> Iterator for ElfTotals<R> {
+    type Item = Result<u64>;
 
-5000
-6000
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut total = None;
+        for line in self.lines.by_ref() {
+            self.line += 1;
+            let error = |snippet: &str, reason: String| AocError::Parse {
+                line: self.line,
+                column: 1,
+                snippet: snippet.chars().take(40).collect(),
+                reason,
+            };
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Some(Err(error("", format!("failed to read input: {err}")))),
+            };
+            let item = line.trim_end_matches('\r');
+            if item.is_empty() {
+                // runs of blank lines don't make empty elves
+                if total.is_some() {
+                    break;
+                }
+                continue;
+            }
+            match item.parse::<u32>() {
+                Ok(calories) => *total.get_or_insert(0) += u64::from(calories),
+                Err(_) => return Some(Err(error(item, "expected a calorie count".to_string()))),
+            }
+        }
+        total.map(Ok)
+    }
+}
 
-7000
-8000
-9000
+/// The `k` elves in `reader` carrying the most calories, most first.
+///
+/// Only the `k` best seen so far are kept while reading.
+pub fn top_n<R: BufRead>(reader: R, k: usize) -> Result<Vec<Ranked>> {
+    let mut podium = Podium::new(k);
+    for (elf, calories) in elf_totals(reader).enumerate() {
+        podium.push(Ranked {
+            elf,
+            calories: calories?,
+        });
+    }
+    Ok(podium.into_ranking())
+}
+
+/// The `k` largest of `totals`, most first.
+pub fn rank(totals: impl IntoIterator<Item = u64>, k: usize) -> Vec<Ranked> {
+    let mut podium = Podium::new(k);
+    for (elf, calories) in totals.into_iter().enumerate() {
+        podium.push(Ranked { elf, calories });
+    }
+    podium.into_ranking()
+}
+
+/// A min-heap of the best `k` elves so far, so the worst of them is the one
+/// to drop. Between equal totals the earlier elf ranks higher.
+struct Podium {
+    k: usize,
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl Podium {
+    fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    fn push(&mut self, ranked: Ranked) {
+        self.heap
+            .push(Reverse((ranked.calories, Reverse(ranked.elf))));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    fn into_ranking(self) -> Vec<Ranked> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(elf)))| Ranked { elf, calories })
+            .collect()
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day01::part1(&Day01::parse(&input)?, &()).map(|answer| answer.to_string())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day01::part2(&Day01::parse(&input)?, &()).map(|answer| answer.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-10000";
+    aoc_common::example_tests!(Day01);
 
     #[test]
-    fn part1_works() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, "24000");
+    fn top_n_reads_elves_incrementally() {
+        let input = format!("{}\r\n\r\n\r\n24000\n", Day01::EXAMPLE);
+        let reader = std::io::BufReader::with_capacity(4, input.as_bytes());
+        let ranked = |elf, calories| Ranked { elf, calories };
+        assert_eq!(
+            top_n(reader, 3).unwrap(),
+            vec![ranked(3, 24000), ranked(5, 24000), ranked(2, 11000)]
+        );
+        assert!(top_n(Day01::EXAMPLE.as_bytes(), 0).unwrap().is_empty());
+        assert_eq!(rank([5, 7], usize::MAX), vec![ranked(1, 7), ranked(0, 5)]);
+        assert_eq!(top_n(Day01::EXAMPLE.as_bytes(), 10).unwrap().len(), 5);
     }
 
     #[test]
-    fn part2_works() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, "45000");
+    fn reports_where_a_load_is_bad() {
+        let err = top_n("1\n\n2\n3x\n".as_bytes(), 1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected a calorie count at line 4, column 1: `3x`"
+        );
     }
 }
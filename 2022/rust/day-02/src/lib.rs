@@ -1,108 +1,359 @@
-use std::str::FromStr;
+use aoc_common::{
+    normalize,
+    params::{FromParams, ParamError, RawParams},
+    tracing::debug,
+    viz::{Cell, Colour},
+    AocError, Frame, Part, Point2, Result, Solution, Viz,
+};
+use std::{fmt, str::FromStr};
 
-#[derive(Copy, Clone)]
-enum Move {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
 }
 
-impl FromStr for Move {
-    type Err = String;
+/// How the second column of the strategy guide is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// The move to play, as in part 1.
+    Move,
+    /// The outcome to aim for, as in part 2.
+    Outcome,
+}
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        match s {
-            "A" | "X" => Ok(Move::Rock),
-            "B" | "Y" => Ok(Move::Paper),
-            "C" | "Z" => Ok(Move::Scissors),
-            _ => Err(String::from("Invalid Move")),
+/// A game of rock paper scissors, or one of its variants, described as data.
+///
+/// Moves are numbered by their position in `moves`, and each column letter
+/// stands for the move at the same position in its list.
+#[derive(Debug)]
+pub struct Rules {
+    pub moves: &'static [&'static str],
+    pub elf_letters: &'static str,
+    pub move_letters: &'static str,
+    /// The letters for a loss, a draw and a win, when the second column is
+    /// read as an outcome.
+    pub outcome_letters: &'static str,
+    /// `beats[m]` lists the moves that move `m` defeats.
+    pub beats: &'static [&'static [usize]],
+    pub move_scores: &'static [u32],
+    /// Scores for a loss, a draw and a win.
+    pub outcome_scores: [u32; 3],
+}
+
+pub const CLASSIC: Rules = Rules {
+    moves: &["rock", "paper", "scissors"],
+    elf_letters: "ABC",
+    move_letters: "XYZ",
+    outcome_letters: "XYZ",
+    beats: &[&[2], &[0], &[1]],
+    move_scores: &[1, 2, 3],
+    outcome_scores: [0, 3, 6],
+};
+
+pub const LIZARD_SPOCK: Rules = Rules {
+    moves: &["rock", "paper", "scissors", "lizard", "spock"],
+    elf_letters: "ABCDE",
+    move_letters: "VWXYZ",
+    outcome_letters: "XYZ",
+    beats: &[&[2, 3], &[0, 4], &[1, 3], &[1, 4], &[0, 2]],
+    move_scores: &[1, 2, 3, 4, 5],
+    outcome_scores: [0, 3, 6],
+};
+
+impl Rules {
+    pub fn outcome(&self, elf: usize, ours: usize) -> Outcome {
+        if self.beats[ours].contains(&elf) {
+            Outcome::Win
+        } else if self.beats[elf].contains(&ours) {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The first move, in the order of `moves`, that gets `wanted` against
+    /// `elf`.
+    pub fn respond(&self, elf: usize, wanted: Outcome) -> Option<usize> {
+        (0..self.moves.len()).find(|&ours| self.outcome(elf, ours) == wanted)
+    }
+
+    /// Plays every round of `guide`, reading its second column as `column`,
+    /// and shows `watch` how each round went and the tally so far.
+    pub fn play(
+        &self,
+        guide: &Guide,
+        column: Column,
+        mut watch: impl FnMut(Outcome, &Tally),
+    ) -> Result<Tally> {
+        let letter = |letters: &str, at: &str| {
+            letters.find(at).filter(|_| at.len() == 1).ok_or_else(|| {
+                AocError::parse(guide.input, at, format!("expected one of {letters}"))
+            })
+        };
+
+        let mut tally = Tally::default();
+        for &(elf, second) in &guide.rounds {
+            let elf_move = letter(self.elf_letters, elf)?;
+            let ours = match column {
+                Column::Move => letter(self.move_letters, second)?,
+                Column::Outcome => {
+                    let wanted = [Outcome::Loss, Outcome::Draw, Outcome::Win]
+                        [letter(self.outcome_letters, second)?];
+                    self.respond(elf_move, wanted).ok_or_else(|| {
+                        AocError::unsolvable(format!(
+                            "no move gets a {wanted:?} against {}",
+                            self.moves[elf_move]
+                        ))
+                    })?
+                }
+            };
+            let outcome = self.outcome(elf_move, ours);
+            tally.score += self.move_scores[ours] + self.outcome_scores[outcome as usize];
+            match outcome {
+                Outcome::Win => tally.wins += 1,
+                Outcome::Draw => tally.draws += 1,
+                Outcome::Loss => tally.losses += 1,
+            }
+            watch(outcome, &tally);
         }
+        debug!(
+            wins = tally.wins,
+            draws = tally.draws,
+            losses = tally.losses,
+            "played the guide"
+        );
+        Ok(tally)
     }
 }
 
-enum Outcome {
-    Win = 6,
-    Draw = 3,
-    Loss = 0,
+/// The score from following a strategy guide, with how its rounds went.
+///
+/// Displays as just the score, which is the puzzle's answer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub score: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
 }
 
-impl FromStr for Outcome {
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.score.fmt(f)
+    }
+}
+
+/// How many rounds each row of a frame holds.
+const ROUNDS_PER_ROW: usize = 50;
+
+/// A cell for each round played so far, in a `columns` by `rows` grid of
+/// them, captioned with the tally.
+fn rounds_frame((columns, rows): (usize, usize), outcomes: &[Outcome], tally: &Tally) -> Frame {
+    let cells = outcomes.iter().enumerate().map(|(round, outcome)| {
+        let at = Point2::new(
+            (round % ROUNDS_PER_ROW) as i32,
+            (round / ROUNDS_PER_ROW) as i32,
+        );
+        let cell = match outcome {
+            Outcome::Win => Cell::new('W', Colour::Green),
+            Outcome::Draw => Cell::new('D', Colour::Yellow),
+            Outcome::Loss => Cell::new('L', Colour::Red),
+        };
+        (at, cell)
+    });
+    let bottom_right = Point2::new(columns as i32 - 1, rows as i32 - 1);
+    Frame::from_points_in(Point2::default(), bottom_right, cells, '.').caption(format!(
+        "round {}: score {}, wins {}, draws {}, losses {}",
+        outcomes.len(),
+        tally.score,
+        tally.wins,
+        tally.draws,
+        tally.losses
+    ))
+}
+
+/// Which [`Rules`] to play by, chosen with `game=classic` or
+/// `game=lizard-spock`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Game {
+    #[default]
+    Classic,
+    LizardSpock,
+}
+
+impl Game {
+    pub fn rules(self) -> &'static Rules {
+        match self {
+            Game::Classic => &CLASSIC,
+            Game::LizardSpock => &LIZARD_SPOCK,
+        }
+    }
+}
+
+impl FromStr for Game {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
-            "X" => Ok(Outcome::Loss),
-            "Y" => Ok(Outcome::Draw),
-            "Z" => Ok(Outcome::Win),
-            _ => Err(String::from("Invalid Result")),
+            "classic" => Ok(Game::Classic),
+            "lizard-spock" => Ok(Game::LizardSpock),
+            _ => Err(format!("unknown game `{s}`")),
         }
     }
 }
 
-pub fn process_part1(input: &str) -> String {
-    let result: u32 = input
-        .lines()
-        .map(|line| {
-            let moves: Vec<Move> = line
-                .split(' ')
-                .map(|s| s.parse::<Move>().unwrap())
-                .collect();
-            match (moves[0], moves[1]) {
-                (Move::Rock, Move::Rock) => moves[1] as u32 + 3,
-                (Move::Rock, Move::Paper) => moves[1] as u32 + 6,
-                (Move::Rock, Move::Scissors) => moves[1] as u32,
-                (Move::Paper, Move::Rock) => moves[1] as u32,
-                (Move::Paper, Move::Paper) => moves[1] as u32 + 3,
-                (Move::Paper, Move::Scissors) => moves[1] as u32 + 6,
-                (Move::Scissors, Move::Rock) => moves[1] as u32 + 6,
-                (Move::Scissors, Move::Paper) => moves[1] as u32,
-                (Move::Scissors, Move::Scissors) => moves[1] as u32 + 3,
-            }
-        })
-        .sum();
-    result.to_string()
-}
-
-pub fn process_part2(input: &str) -> String {
-    let result: u32 = input
-        .lines()
-        .map(|line| {
-            let items: Vec<&str> = line.split(' ').collect();
-            let elf: Move = items[0].parse().unwrap();
-            let outcome: Outcome = items[1].parse().unwrap();
-            match (elf, outcome) {
-                (Move::Rock, Outcome::Win) => Move::Paper as u32 + 6,
-                (Move::Rock, Outcome::Draw) => Move::Rock as u32 + 3,
-                (Move::Rock, Outcome::Loss) => Move::Scissors as u32,
-                (Move::Paper, Outcome::Win) => Move::Scissors as u32 + 6,
-                (Move::Paper, Outcome::Draw) => Move::Paper as u32 + 3,
-                (Move::Paper, Outcome::Loss) => Move::Rock as u32,
-                (Move::Scissors, Outcome::Win) => Move::Rock as u32 + 6,
-                (Move::Scissors, Outcome::Draw) => Move::Scissors as u32 + 3,
-                (Move::Scissors, Outcome::Loss) => Move::Paper as u32,
-            }
+#[derive(Debug, Default)]
+pub struct Day02Params {
+    pub game: Game,
+}
+
+impl FromParams for Day02Params {
+    fn from_params(params: &RawParams) -> Result<Self, ParamError> {
+        Ok(Self {
+            game: params.get_or("game", Game::Classic)?,
         })
-        .sum();
-    result.to_string()
+    }
+}
+
+/// The strategy guide's two columns, left as letters until the rules say
+/// what they mean.
+pub struct Guide<'a> {
+    input: &'a str,
+    rounds: Vec<(&'a str, &'a str)>,
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 2;
+    const EXAMPLE: &'static str = include_str!("../examples/02-a.txt");
+
+    type Parsed<'a> = Guide<'a>;
+    type Params = Day02Params;
+    type Answer1 = Tally;
+    type Answer2 = Tally;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        let rounds = input
+            .lines()
+            .map(|line| {
+                line.split_once(' ')
+                    .ok_or_else(|| AocError::parse(input, line, "expected two columns"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Guide { input, rounds })
+    }
+
+    fn part1(guide: &Self::Parsed<'_>, params: &Day02Params) -> Result<Tally> {
+        params.game.rules().play(guide, Column::Move, |_, _| {})
+    }
+
+    fn part2(guide: &Self::Parsed<'_>, params: &Day02Params) -> Result<Tally> {
+        params.game.rules().play(guide, Column::Outcome, |_, _| {})
+    }
+
+    fn visualize(
+        guide: &Self::Parsed<'_>,
+        params: &Day02Params,
+        part: Part,
+        viz: &mut Viz,
+    ) -> Result<String> {
+        let column = match part {
+            Part::One => Column::Move,
+            Part::Two => Column::Outcome,
+        };
+        let rounds = guide.rounds.len();
+        let size = (rounds.min(ROUNDS_PER_ROW), rounds.div_ceil(ROUNDS_PER_ROW));
+        let mut outcomes = vec![];
+        let tally = params.game.rules().play(guide, column, |outcome, tally| {
+            outcomes.push(outcome);
+            viz.frame(|| rounds_frame(size, &outcomes, tally));
+        })?;
+        viz.last_frame(|| rounds_frame(size, &outcomes, &tally));
+        Ok(tally.to_string())
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day02::part1(&Day02::parse(&input)?, &Day02Params::default()).map(|answer| answer.to_string())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day02::part2(&Day02::parse(&input)?, &Day02Params::default()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::viz::TextLog;
+
+    aoc_common::example_tests!(Day02);
 
-    const INPUT: &str = "A Y
-B X
-C Z";
+    #[test]
+    fn plays_lizard_spock_with_stats() {
+        let guide = Day02::parse("A V\nD Z\nE W").unwrap();
+        let tally = LIZARD_SPOCK.play(&guide, Column::Move, |_, _| {}).unwrap();
+        assert_eq!(
+            tally,
+            Tally {
+                score: 4 + 5 + 8,
+                wins: 1,
+                draws: 1,
+                losses: 1
+            }
+        );
+
+        let guide = Day02::parse("E Z\nB X").unwrap();
+        let tally = LIZARD_SPOCK
+            .play(&guide, Column::Outcome, |_, _| {})
+            .unwrap();
+        assert_eq!((tally.score, tally.wins, tally.losses), (8 + 1, 1, 1));
+
+        let guide = Day02::parse("A V\nF V").unwrap();
+        let err = LIZARD_SPOCK
+            .play(&guide, Column::Move, |_, _| {})
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected one of ABCDE at line 2, column 1: `F V`"
+        );
+    }
 
     #[test]
-    fn part1_works() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, "15");
+    fn visualizes_the_tally_round_by_round() {
+        let guide = Day02::parse(Day02::EXAMPLE).unwrap();
+        let mut out = vec![];
+        let mut log = TextLog::new(&mut out);
+        let mut viz = Viz::new(&mut log);
+        let params = Day02Params::default();
+        let answer = Day02::visualize(&guide, &params, Part::Two, &mut viz).unwrap();
+        assert_eq!(answer, "12");
+        assert_eq!(viz.finish().unwrap(), 3);
+        let log = String::from_utf8(out).unwrap();
+        assert!(log.ends_with("frame 3: round 3: score 12, wins 1, draws 1, losses 1\nDLW\n\n"));
     }
 
     #[test]
-    fn part2_works() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, "12");
+    fn every_pair_of_moves_has_one_winner() {
+        for rules in [&CLASSIC, &LIZARD_SPOCK] {
+            let moves = rules.moves.len();
+            assert_eq!(rules.elf_letters.len(), moves);
+            assert_eq!(rules.move_letters.len(), moves);
+            assert_eq!(rules.move_scores.len(), moves);
+            for elf in 0..moves {
+                assert!(!rules.beats[elf].contains(&elf));
+                for ours in (0..moves).filter(|&ours| ours != elf) {
+                    assert_ne!(
+                        rules.beats[elf].contains(&ours),
+                        rules.beats[ours].contains(&elf)
+                    );
+                }
+            }
+        }
     }
 }
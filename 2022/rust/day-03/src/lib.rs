@@ -1,68 +1,250 @@
-use std::collections::HashSet;
-
-pub fn process_part1(input: &str) -> String {
-    let result: u32 = input
-        .lines()
-        .map(|s| {
-            let (front, back) = s.split_at(s.len() / 2);
-            let front_set: HashSet<u8> = HashSet::from_iter(front.bytes());
-            let back_set: HashSet<u8> = HashSet::from_iter(back.bytes());
-            let common = front_set.intersection(&back_set).next().unwrap();
-            *common
+use aoc_common::{
+    normalize,
+    params::{FromParams, ParamError, RawParams},
+    AocError, Result, Solution,
+};
+use std::{num::NonZeroUsize, ops::BitAnd};
+
+/// A set of item types, one bit each from `a` up to `Z`, so that an item's
+/// priority is its bit's position plus one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Items(u64);
+
+impl Items {
+    pub const ALL: Items = Items((1 << 52) - 1);
+
+    /// The item types in `rucksack`, which [`Day03::parse`] has checked only
+    /// holds letters.
+    fn of(rucksack: &str) -> Self {
+        Items(
+            rucksack
+                .bytes()
+                .fold(0, |items, item| items | 1 << (priority(item) - 1)),
+        )
+    }
+
+    pub fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn priorities(self) -> impl Iterator<Item = u32> {
+        (0..52)
+            .filter(move |bit| self.0 & 1 << bit != 0)
+            .map(|bit| bit + 1)
+    }
+}
+
+impl BitAnd for Items {
+    type Output = Items;
+
+    fn bitand(self, other: Items) -> Items {
+        Items(self.0 & other.0)
+    }
+}
+
+/// The rucksacks, one per line, and the input they came from so problems
+/// can point at a line.
+pub struct Rucksacks<'a> {
+    input: &'a str,
+    lines: Vec<&'a str>,
+}
+
+#[derive(Debug)]
+pub struct Day03Params {
+    pub group_size: NonZeroUsize,
+}
+
+impl Default for Day03Params {
+    fn default() -> Self {
+        Self {
+            group_size: GROUP_SIZE,
+        }
+    }
+}
+
+impl FromParams for Day03Params {
+    fn from_params(params: &RawParams) -> Result<Self, ParamError> {
+        Ok(Self {
+            group_size: params.get_or("group_size", GROUP_SIZE)?,
         })
-		.map(|n| match n {
-            b'a'..=b'z' => n - b'a',
-            b'A'..=b'Z' => n - b'A' + 26,
-            _ => panic!("Input must only be alphabetical"),
-        } + 1)
-        .map(u32::from)
-        .sum();
-    result.to_string()
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    let result: u32 = input
-        .lines()
-        .map(str::bytes)
-        .map(HashSet::from_iter)
-        .collect::<Vec<HashSet<u8>>>()
-        .chunks_exact(3)
-        .map(|chunk| {
-            let [first, second, third] = chunk else { unreachable!() };
-            let first_second_intersect: HashSet<u8> = first.intersection(second).copied().collect();
-            let common = first_second_intersect.intersection(third).next().unwrap();
-            *common
+const GROUP_SIZE: NonZeroUsize = NonZeroUsize::new(3).unwrap();
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 3;
+    const EXAMPLE: &'static str = include_str!("../examples/03-a.txt");
+
+    type Parsed<'a> = Rucksacks<'a>;
+    type Params = Day03Params;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        let lines = input
+            .lines()
+            .map(|line| match line.find(|c: char| !c.is_ascii_alphabetic()) {
+                Some(at) => Err(AocError::parse(input, &line[at..], "expected a letter")),
+                None => Ok(line),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Rucksacks { input, lines })
+    }
+
+    fn part1(rucksacks: &Self::Parsed<'_>, _params: &Day03Params) -> Result<u32> {
+        misplaced(rucksacks)
+    }
+
+    fn part2(rucksacks: &Self::Parsed<'_>, params: &Day03Params) -> Result<u32> {
+        badges(rucksacks, params.group_size)
+    }
+}
+
+/// The sum of the priorities of the one item type in both compartments of
+/// each rucksack.
+pub fn misplaced(rucksacks: &Rucksacks) -> Result<u32> {
+    rucksacks
+        .lines
+        .iter()
+        .map(|&line| {
+            if line.len() % 2 != 0 {
+                return Err(AocError::parse(
+                    rucksacks.input,
+                    line,
+                    format!("expected an even number of items, found {}", line.len()),
+                ));
+            }
+            let (front, back) = line.split_at(line.len() / 2);
+            let shared = Items::of(front) & Items::of(back);
+            only(rucksacks.input, line, shared, "item in both compartments")
+        })
+        .sum()
+}
+
+/// The sum of the priorities of the badge each group of `group_size`
+/// rucksacks in a row has in common.
+pub fn badges(rucksacks: &Rucksacks, group_size: NonZeroUsize) -> Result<u32> {
+    rucksacks
+        .lines
+        .chunks(group_size.get())
+        .map(|group| {
+            if group.len() < group_size.get() {
+                return Err(AocError::parse(
+                    rucksacks.input,
+                    group[0],
+                    format!(
+                        "expected a group of {group_size} rucksacks, found {}",
+                        group.len()
+                    ),
+                ));
+            }
+            let shared = group
+                .iter()
+                .fold(Items::ALL, |shared, line| shared & Items::of(line));
+            let what = format!("badge shared by a group of {group_size}");
+            only(rucksacks.input, group[0], shared, &what)
         })
-        .map(|n| match n {
-            b'a'..=b'z' => n - b'a',
-            b'A'..=b'Z' => n - b'A' + 26,
-            _ => panic!("Input must only be alphabetical"),
-        } + 1)
-        .map(u32::from)
-        .sum();
-    result.to_string()
+        .sum()
+}
+
+/// The priority of the one item type in `shared`, or an error at `line`
+/// saying how many there were instead.
+fn only(input: &str, line: &str, shared: Items, what: &str) -> Result<u32> {
+    let mut priorities = shared.priorities();
+    match (priorities.next(), priorities.next()) {
+        (Some(priority), None) => Ok(priority),
+        (None, _) => Err(AocError::parse(
+            input,
+            line,
+            format!("expected one {what}, found none"),
+        )),
+        (Some(_), Some(_)) => {
+            let items: Vec<_> = shared
+                .priorities()
+                .map(|priority| format!("`{}`", item(priority)))
+                .collect();
+            Err(AocError::parse(
+                input,
+                line,
+                format!(
+                    "expected one {what}, found {} ({})",
+                    items.len(),
+                    items.join(", ")
+                ),
+            ))
+        }
+    }
+}
+
+fn priority(item: u8) -> u32 {
+    let priority = match item {
+        b'a'..=b'z' => item - b'a',
+        b'A'..=b'Z' => item - b'A' + 26,
+        _ => unreachable!("parse only accepts letters"),
+    } + 1;
+    u32::from(priority)
+}
+
+fn item(priority: u32) -> char {
+    let item = (b'a'..=b'z').chain(b'A'..=b'Z').nth(priority as usize - 1);
+    char::from(item.expect("priorities run from 1 to 52"))
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day03::part1(&Day03::parse(&input)?, &Day03Params::default()).map(|answer| answer.to_string())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day03::part2(&Day03::parse(&input)?, &Day03Params::default()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
+    aoc_common::example_tests!(Day03);
 
     #[test]
-    fn part1_works() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, "157");
+    fn groups_can_be_any_size() {
+        let rucksacks = Day03::parse("ab\nbc\ncb\nbd").unwrap();
+        let badges = |size| badges(&rucksacks, NonZeroUsize::new(size).unwrap());
+        assert_eq!(badges(4).unwrap(), 2);
+        assert_eq!(badges(2).unwrap(), 4);
+        assert_eq!(
+            badges(3).unwrap_err().to_string(),
+            "expected a group of 3 rucksacks, found 1 at line 4, column 1: `bd`"
+        );
     }
 
     #[test]
-    fn part2_works() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, "70");
+    fn reports_rucksacks_without_exactly_one_shared_item() {
+        let error = |input| {
+            misplaced(&Day03::parse(input).unwrap())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("aa\nabcd"),
+            "expected one item in both compartments, found none at line 2, column 1: `abcd`"
+        );
+        assert_eq!(
+            error("aZbaZb"),
+            "expected one item in both compartments, found 3 (`a`, `b`, `Z`) \
+             at line 1, column 1: `aZbaZb`"
+        );
+        assert_eq!(
+            error("aa\nabc"),
+            "expected an even number of items, found 3 at line 2, column 1: `abc`"
+        );
     }
 }
@@ -1,73 +1,112 @@
-use nom::{bytes::complete::tag, character::complete, IResult};
-use std::ops::RangeInclusive;
+use aoc_common::{
+    normalize,
+    parse::{lines, parse_all},
+    Interval, Result, Solution,
+};
+use nom::{
+    bytes::complete::tag, character::complete, combinator::map_opt, sequence::separated_pair,
+    IResult,
+};
 
-type RangePair = (RangeInclusive<u32>, RangeInclusive<u32>);
+type Assignment = Interval<u64>;
+type AssignmentPair = (Assignment, Assignment);
 
-fn range(input: &str) -> IResult<&str, RangeInclusive<u32>> {
-    let (input, start) = complete::u32(input)?;
-    let (input, _) = tag("-")(input)?;
-    let (input, end) = complete::u32(input)?;
-    Ok((input, start..=end))
+fn assignment(input: &str) -> IResult<&str, Assignment> {
+    map_opt(
+        separated_pair(complete::u64, tag("-"), complete::u64),
+        |(start, end)| Interval::new(start, end),
+    )(input)
 }
 
-fn range_pair(input: &str) -> IResult<&str, RangePair> {
-    let (input, first) = range(input)?;
-    let (input, _) = tag(",")(input)?;
-    let (input, second) = range(input)?;
-    Ok((input, (first, second)))
+fn assignment_pair(input: &str) -> IResult<&str, AssignmentPair> {
+    separated_pair(assignment, tag(","), assignment)(input)
 }
 
-fn section_assignments(input: &str) -> IResult<&str, Vec<RangePair>> {
-    let (input, ranges) = nom::multi::separated_list1(complete::newline, range_pair)(input)?;
-    Ok((input, ranges))
+fn section_assignments(input: &str) -> IResult<&str, Vec<AssignmentPair>> {
+    lines(assignment_pair)(input)
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, assignments) = section_assignments(input).unwrap();
-    let result = assignments
-        .iter()
-        .filter(|(range_a, range_b)| {
-            let a_contains_b = range_a.clone().all(|num| range_b.contains(&num));
-            let b_contains_a = range_b.clone().all(|num| range_a.contains(&num));
-            a_contains_b || b_contains_a
-        })
-        .count();
-    result.to_string()
+pub struct Day04;
+
+impl Solution for Day04 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 4;
+    const EXAMPLE: &'static str = include_str!("../examples/04-a.txt");
+
+    type Parsed<'a> = Vec<AssignmentPair>;
+    type Params = ();
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        parse_all(input, section_assignments)
+    }
+
+    fn part1(assignments: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        Ok(assignments
+            .iter()
+            .filter(|(a, b)| a.covers(*b) || b.covers(*a))
+            .count())
+    }
+
+    fn part2(assignments: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        Ok(assignments.iter().filter(|(a, b)| a.overlaps(*b)).count())
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, assignments) = section_assignments(input).unwrap();
-    let result = assignments
-        .iter()
-        .filter(|(range_a, range_b)| {
-            let a_contains_b = range_a.clone().any(|num| range_b.contains(&num));
-            let b_contains_a = range_b.clone().any(|num| range_a.contains(&num));
-            a_contains_b || b_contains_a
-        })
-        .count();
-    result.to_string()
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day04::part1(&Day04::parse(&input)?, &()).map(|answer| answer.to_string())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day04::part2(&Day04::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::testing::range_pairs;
+    use std::ops::RangeInclusive;
 
-    const INPUT: &str = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
+    aoc_common::example_tests!(Day04);
 
-    #[test]
-    fn part1_works() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, "2");
+    fn ranges(assignments: &[AssignmentPair]) -> Vec<(RangeInclusive<u64>, RangeInclusive<u64>)> {
+        assignments
+            .iter()
+            .map(|&(a, b)| (a.into(), b.into()))
+            .collect()
+    }
+
+    /// Checks containment by testing every section in each range.
+    fn scan_part1(assignments: &[AssignmentPair], _params: &()) -> Result<usize> {
+        Ok(ranges(assignments)
+            .iter()
+            .filter(|(a, b)| a.clone().all(|n| b.contains(&n)) || b.clone().all(|n| a.contains(&n)))
+            .count())
+    }
+
+    /// Checks overlap by testing every section in the first range.
+    fn scan_part2(assignments: &[AssignmentPair], _params: &()) -> Result<usize> {
+        Ok(ranges(assignments)
+            .iter()
+            .filter(|(a, b)| a.clone().any(|n| b.contains(&n)))
+            .count())
     }
 
     #[test]
-    fn part2_works() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, "4");
+    fn handles_sections_across_all_of_u64() {
+        let input = "0-18446744073709551615,9223372036854775808-18446744073709551614
+1-2,18446744073709551613-18446744073709551615";
+        assert_eq!(process_part1(input).unwrap(), "1");
+        assert_eq!(process_part2(input).unwrap(), "1");
+        assert!(process_part1("4-2,1-5").is_err());
+    }
+
+    aoc_common::differential_tests! {
+        Day04;
+        part1_matches_scan: part1 => scan_part1, range_pairs(60, 1..30);
+        part2_matches_scan: part2 => scan_part2, range_pairs(60, 1..30);
     }
 }
@@ -1,26 +1,46 @@
+use aoc_common::{
+    normalize,
+    params::{FromParams, ParamError, RawParams},
+    parse::parse_all,
+    AocError, Frame, Grid, Part, Result, Solution, Viz,
+};
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{self, alpha1, digit1, multispace1, newline},
+    combinator::verify,
     multi::{many1, separated_list1},
     sequence::{delimited, preceded},
     IResult,
 };
+use std::{fmt, mem, num::NonZeroUsize};
 
-#[derive(Debug)]
-struct Command {
+#[derive(Debug, Clone, Copy)]
+pub struct Command {
     count: u32,
     from: u32,
     to: u32,
 }
 
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
 fn move_command(input: &str) -> IResult<&str, Command> {
     let (input, _) = tag("move ")(input)?;
     let (input, count) = complete::u32(input)?;
     let (input, _) = tag(" from ")(input)?;
-    let (input, from) = complete::u32(input)?;
+    let (input, from) = verify(complete::u32, |from| *from > 0)(input)?;
     let (input, _) = tag(" to ")(input)?;
-    let (input, to) = complete::u32(input)?;
+    let (input, to) = verify(complete::u32, |to| *to > 0)(input)?;
 
     Ok((
         input,
@@ -49,7 +69,7 @@ fn line(input: &str) -> IResult<&str, Vec<Option<&str>>> {
     Ok((input, result))
 }
 
-fn stacks_and_commands(input: &str) -> IResult<&str, (Vec<Vec<&str>>, Vec<Command>)> {
+fn stacks_and_commands(input: &str) -> IResult<&str, (Stacks<'_>, Vec<Command>)> {
     let (input, crates_by_row) = separated_list1(newline, line)(input)?;
     let (input, _) = newline(input)?;
     let (input, _numbers) = many1(preceded(multispace1, digit1))(input)?;
@@ -57,7 +77,8 @@ fn stacks_and_commands(input: &str) -> IResult<&str, (Vec<Vec<&str>>, Vec<Comman
     let (input, commands) = separated_list1(newline, move_command)(input)?;
 
     let mut crates_by_column: Vec<Vec<Option<&str>>> = vec![];
-    for _ in 0..crates_by_row[0].len() {
+    let columns = crates_by_row.iter().map(Vec::len).max().unwrap_or_default();
+    for _ in 0..columns {
         crates_by_column.push(vec![]);
     }
     for row in crates_by_row.iter().rev() {
@@ -65,7 +86,7 @@ fn stacks_and_commands(input: &str) -> IResult<&str, (Vec<Vec<&str>>, Vec<Comman
             crates_by_column[i].push(*c);
         }
     }
-    let stacks: Vec<Vec<&str>> = crates_by_column
+    let stacks: Stacks = crates_by_column
         .iter()
         .map(|col| col.iter().filter_map(|v| *v).collect())
         .collect();
@@ -73,76 +94,421 @@ fn stacks_and_commands(input: &str) -> IResult<&str, (Vec<Vec<&str>>, Vec<Comman
     Ok((input, (stacks, commands)))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, (mut crate_stacks, commands)) = stacks_and_commands(input).unwrap();
-    for Command { count, from, to } in commands {
-        let len = crate_stacks[from as usize].len();
-        for c in crate_stacks[from as usize]
-            .drain((len - count as usize)..)
-            .rev()
-            .collect::<Vec<&str>>()
-        {
-            crate_stacks[to as usize].push(c);
+type Stacks<'a> = Vec<Vec<&'a str>>;
+
+/// Which crane to move crates with, chosen with `crane=9000`, `crane=9001`
+/// or `crane=batch` together with `batch=<n>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CraneModel {
+    CrateMover9000,
+    CrateMover9001,
+    BatchLimited(NonZeroUsize),
+}
+
+impl Crane for CraneModel {
+    fn carry<'a>(&self, count: usize, from: &mut Vec<&'a str>, to: &mut Vec<&'a str>) {
+        match *self {
+            CraneModel::CrateMover9000 => CrateMover9000.carry(count, from, to),
+            CraneModel::CrateMover9001 => CrateMover9001.carry(count, from, to),
+            CraneModel::BatchLimited(batch) => BatchLimited { batch }.carry(count, from, to),
         }
     }
+}
 
-    let result: String = crate_stacks
-        .iter()
-        .map(|v| match v.iter().last() {
-            Some(c) => c,
-            None => "",
+/// Without a `crane`, part 1 uses the CrateMover 9000 and part 2 the 9001.
+#[derive(Debug, Default)]
+pub struct Day05Params {
+    pub crane: Option<CraneModel>,
+}
+
+impl Day05Params {
+    pub fn crane(&self, part: Part) -> CraneModel {
+        self.crane.unwrap_or(match part {
+            Part::One => CraneModel::CrateMover9000,
+            Part::Two => CraneModel::CrateMover9001,
         })
-        .collect();
+    }
+}
+
+impl FromParams for Day05Params {
+    fn from_params(params: &RawParams) -> Result<Self, ParamError> {
+        let batch = params.get::<NonZeroUsize>("batch")?;
+        let crane = match params.get::<String>("crane")?.as_deref() {
+            None => None,
+            Some("9000") => Some(CraneModel::CrateMover9000),
+            Some("9001") => Some(CraneModel::CrateMover9001),
+            Some("batch") => Some(CraneModel::BatchLimited(
+                batch.ok_or_else(|| ParamError::Missing("batch".to_string()))?,
+            )),
+            Some(value) => {
+                return Err(ParamError::Invalid {
+                    key: "crane".to_string(),
+                    value: value.to_string(),
+                })
+            }
+        };
+        Ok(Self { crane })
+    }
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 5;
+    const EXAMPLE: &'static str = include_str!("../examples/05-a.txt");
+
+    type Parsed<'a> = (Stacks<'a>, Vec<Command>);
+    type Params = Day05Params;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        parse_all(input, stacks_and_commands)
+    }
+
+    fn part1((crate_stacks, commands): &Self::Parsed<'_>, params: &Day05Params) -> Result<String> {
+        let crane = params.crane(Part::One);
+        let crate_stacks = replay(crate_stacks, commands, &crane, |_, _| {})?;
+        Ok(top_crates(&crate_stacks))
+    }
+
+    fn part2((crate_stacks, commands): &Self::Parsed<'_>, params: &Day05Params) -> Result<String> {
+        let crane = params.crane(Part::Two);
+        let crate_stacks = replay(crate_stacks, commands, &crane, |_, _| {})?;
+        Ok(top_crates(&crate_stacks))
+    }
+
+    fn visualize(
+        (crate_stacks, commands): &Self::Parsed<'_>,
+        params: &Day05Params,
+        part: Part,
+        viz: &mut Viz,
+    ) -> Result<String> {
+        let crane = params.crane(part);
+        viz.frame(|| stacks_frame(crate_stacks).caption("start"));
+        let mut steps = 0;
+        let crate_stacks = replay(crate_stacks, commands, &crane, |command, crate_stacks| {
+            steps += 1;
+            viz.frame(|| stacks_frame(crate_stacks).caption(format!("step {steps}: {command}")));
+        })?;
+        viz.last_frame(|| stacks_frame(&crate_stacks).caption(format!("step {steps}")));
+        Ok(top_crates(&crate_stacks))
+    }
+}
+
+/// How a model of crane carries crates from one stack to another.
+pub trait Crane {
+    /// Moves the top `count` crates of `from` onto `to`. `from` is known to
+    /// hold at least `count` crates.
+    fn carry<'a>(&self, count: usize, from: &mut Vec<&'a str>, to: &mut Vec<&'a str>);
+}
+
+/// Lifts one crate at a time, so the crates moved end up in reverse order.
+pub struct CrateMover9000;
+
+/// Lifts all the crates moved at once, keeping their order.
+pub struct CrateMover9001;
+
+/// Lifts up to `batch` crates at a time. Each batch keeps its order, but the
+/// batches land in reverse.
+pub struct BatchLimited {
+    pub batch: NonZeroUsize,
+}
+
+fn carry_block<'a>(count: usize, from: &mut Vec<&'a str>, to: &mut Vec<&'a str>) {
+    let at = from.len() - count;
+    to.extend(from.drain(at..));
+}
+
+impl Crane for CrateMover9000 {
+    fn carry<'a>(&self, count: usize, from: &mut Vec<&'a str>, to: &mut Vec<&'a str>) {
+        let at = from.len() - count;
+        to.extend(from.drain(at..).rev());
+    }
+}
 
-    result.to_string()
+impl Crane for CrateMover9001 {
+    fn carry<'a>(&self, count: usize, from: &mut Vec<&'a str>, to: &mut Vec<&'a str>) {
+        carry_block(count, from, to);
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, (mut crate_stacks, commands)) = stacks_and_commands(input).unwrap();
-    for Command { count, from, to } in commands {
-        let len = crate_stacks[from as usize].len();
-        for c in crate_stacks[from as usize]
-            .drain((len - count as usize)..)
-            .collect::<Vec<&str>>()
-        {
-            crate_stacks[to as usize].push(c);
+impl Crane for BatchLimited {
+    fn carry<'a>(&self, count: usize, from: &mut Vec<&'a str>, to: &mut Vec<&'a str>) {
+        let mut left = count;
+        while left > 0 {
+            let batch = left.min(self.batch.get());
+            carry_block(batch, from, to);
+            left -= batch;
         }
     }
+}
+
+/// Why a move can't be made, with stacks numbered from 1 as in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    NoSuchStack { stack: u32, stacks: usize },
+    NotEnoughCrates { stack: u32, held: usize },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::NoSuchStack { stack, stacks } => {
+                write!(f, "there is no stack {stack}, only {stacks}")
+            }
+            MoveError::NotEnoughCrates { stack, held: 0 } => write!(f, "stack {stack} is empty"),
+            MoveError::NotEnoughCrates { stack, held } => {
+                write!(f, "stack {stack} only holds {held}")
+            }
+        }
+    }
+}
+
+/// Checks that `command` can be made before `crane` makes it, so a bad move
+/// leaves `crate_stacks` as they were.
+pub fn execute<'a>(
+    crate_stacks: &mut Stacks<'a>,
+    command: &Command,
+    crane: &dyn Crane,
+) -> std::result::Result<(), MoveError> {
+    let Command { count, from, to } = *command;
+    for stack in [from, to] {
+        if stack as usize >= crate_stacks.len() {
+            return Err(MoveError::NoSuchStack {
+                stack: stack + 1,
+                stacks: crate_stacks.len(),
+            });
+        }
+    }
+    let held = crate_stacks[from as usize].len();
+    if held < count as usize {
+        return Err(MoveError::NotEnoughCrates {
+            stack: from + 1,
+            held,
+        });
+    }
+    // every crane puts crates back just as they were on their own stack
+    if from == to {
+        return Ok(());
+    }
+
+    let mut source = mem::take(&mut crate_stacks[from as usize]);
+    crane.carry(count as usize, &mut source, &mut crate_stacks[to as usize]);
+    crate_stacks[from as usize] = source;
+    Ok(())
+}
+
+/// Makes each of `commands` in turn with `crane`, calling `step` with the
+/// stacks after every move.
+pub fn replay<'a>(
+    crate_stacks: &Stacks<'a>,
+    commands: &[Command],
+    crane: &dyn Crane,
+    mut step: impl FnMut(&Command, &Stacks<'a>),
+) -> Result<Stacks<'a>> {
+    let mut crate_stacks = crate_stacks.clone();
+    for (n, command) in commands.iter().enumerate() {
+        execute(&mut crate_stacks, command, crane).map_err(|err| {
+            AocError::unsolvable(format!("can't make move {} `{command}`: {err}", n + 1))
+        })?;
+        step(command, &crate_stacks);
+    }
+    Ok(crate_stacks)
+}
+
+/// Draws the stacks the way the input does, with a row of stack numbers
+/// underneath.
+pub fn render(crate_stacks: &Stacks) -> String {
+    let height = crate_stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lines: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            crate_stacks
+                .iter()
+                .map(|stack| {
+                    stack
+                        .get(level)
+                        .map_or("   ".to_string(), |c| format!("[{c}]"))
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    let numbers = (1..=crate_stacks.len()).map(|n| format!(" {n} "));
+    lines.push(numbers.collect::<Vec<_>>().join(" "));
+    lines.join("\n")
+}
+
+fn stacks_frame(crate_stacks: &Stacks) -> Frame {
+    let mut rows: Vec<Vec<char>> = render(crate_stacks)
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect();
+    // crates with longer names make some rows wider than others
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(width, ' ');
+    }
+    Frame::new(&Grid::from_rows(rows).expect("rows are padded to the same width"))
+}
 
-    let result: String = crate_stacks
+fn top_crates(crate_stacks: &Stacks) -> String {
+    crate_stacks
         .iter()
         .map(|v| match v.iter().last() {
             Some(c) => c,
             None => "",
         })
-        .collect();
+        .collect()
+}
 
-    result.to_string()
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day05::part1(&Day05::parse(&input)?, &Day05Params::default())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day05::part2(&Day05::parse(&input)?, &Day05Params::default())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
+    aoc_common::example_tests!(Day05);
 
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
+    #[test]
+    fn renders_stacks_as_the_input_draws_them() {
+        let (crate_stacks, commands) = Day05::parse(Day05::EXAMPLE).unwrap();
+        assert_eq!(
+            Some(render(&crate_stacks).as_str()),
+            Day05::EXAMPLE.split("\n\n").next()
+        );
+
+        let mut drawings = vec![];
+        replay(
+            &crate_stacks,
+            &commands,
+            &CrateMover9001,
+            |_, crate_stacks| {
+                drawings.push(render(crate_stacks));
+            },
+        )
+        .unwrap();
+        let last = format!("{}\n\nmove 1 from 1 to 2", drawings[3]);
+        let (reparsed, _) = Day05::parse(&last).unwrap();
+        assert_eq!(top_crates(&reparsed), "MCD");
+    }
+
+    #[test]
+    fn rejects_input_after_the_moves() {
+        let input = format!("{}\nthis is garbage", Day05::EXAMPLE.trim_end());
+        let err = Day05::parse(&input).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "unexpected input at line 10, column 1: `this is garbage`"
+        );
+    }
 
     #[test]
-    fn part1_works() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, "CMZ");
+    fn cranes_differ_in_how_many_crates_they_lift() {
+        let (crate_stacks, _) = Day05::parse(
+            "[E]    \n[D]    \n[C]    \n[B]    \n[A]    \n 1   2 \n\nmove 5 from 1 to 2",
+        )
+        .unwrap();
+        let moved = |crane: &dyn Crane| {
+            let mut crate_stacks = crate_stacks.clone();
+            execute(
+                &mut crate_stacks,
+                &move_command("move 5 from 1 to 2").unwrap().1,
+                crane,
+            )
+            .unwrap();
+            crate_stacks[1].concat()
+        };
+        assert_eq!(moved(&CrateMover9000), "EDCBA");
+        assert_eq!(moved(&CrateMover9001), "ABCDE");
+        let batch = NonZeroUsize::new(2).unwrap();
+        assert_eq!(moved(&BatchLimited { batch }), "DEBCA");
     }
 
     #[test]
-    fn part2_works() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, "MCD");
+    fn reports_impossible_moves() {
+        let (crate_stacks, _) = Day05::parse(Day05::EXAMPLE).unwrap();
+        let error = |command| {
+            let (_, command) = move_command(command).unwrap();
+            let err = replay(&crate_stacks, &[command], &CrateMover9000, |_, _| {});
+            err.unwrap_err().to_string()
+        };
+        assert_eq!(
+            error("move 4 from 1 to 2"),
+            "no solution: can't make move 1 `move 4 from 1 to 2`: stack 1 only holds 2"
+        );
+        assert_eq!(
+            error("move 1 from 2 to 4"),
+            "no solution: can't make move 1 `move 1 from 2 to 4`: there is no stack 4, only 3"
+        );
+
+        let mut emptied = crate_stacks.clone();
+        emptied[2].clear();
+        let (_, command) = move_command("move 1 from 3 to 1").unwrap();
+        assert_eq!(
+            execute(&mut emptied, &command, &CrateMover9000),
+            Err(MoveError::NotEnoughCrates { stack: 3, held: 0 })
+        );
+    }
+
+    #[test]
+    fn reads_the_crane_from_params() {
+        let params = |pairs: &[(&str, &str)]| {
+            let raw: RawParams = pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            Day05Params::from_params(&raw)
+        };
+        let batch = NonZeroUsize::new(2).unwrap();
+        assert_eq!(
+            params(&[]).unwrap().crane(Part::One),
+            CraneModel::CrateMover9000
+        );
+        assert_eq!(
+            params(&[]).unwrap().crane(Part::Two),
+            CraneModel::CrateMover9001
+        );
+        assert_eq!(
+            params(&[("crane", "9001")]).unwrap().crane(Part::One),
+            CraneModel::CrateMover9001
+        );
+        assert_eq!(
+            params(&[("crane", "batch"), ("batch", "2")]).unwrap().crane,
+            Some(CraneModel::BatchLimited(batch))
+        );
+        assert_eq!(
+            params(&[("crane", "9002")]).unwrap_err().to_string(),
+            "invalid value `9002` for parameter `crane`"
+        );
+        assert_eq!(
+            params(&[("crane", "batch"), ("batch", "0")])
+                .unwrap_err()
+                .to_string(),
+            "invalid value `0` for parameter `batch`"
+        );
+        assert_eq!(
+            params(&[("crane", "batch")]).unwrap_err().to_string(),
+            "missing parameter `batch`"
+        );
+
+        let batched = [("crane", "batch"), ("batch", "2")];
+        aoc_common::examples::check::<Day05>(Day05::EXAMPLE, Part::One, "MCZ", &batched);
+        aoc_common::examples::check::<Day05>(
+            Day05::EXAMPLE,
+            Part::Two,
+            "CMZ",
+            &[("crane", "9000")],
+        );
     }
 }
@@ -1,50 +1,60 @@
+use aoc_common::{normalize, AocError, Result, Solution};
 use std::collections::HashSet;
 
-pub fn process_part1(input: &str) -> String {
-    let bytes = input.bytes().collect::<Vec<u8>>();
-    let sequence = bytes
-        .windows(4)
-        .enumerate()
-        .find(|(_i, slice)| {
-            let set = slice.iter().collect::<HashSet<&u8>>();
-            slice.len() == set.len()
-        })
-        .unwrap();
-    (sequence.0 + 1 + 3).to_string()
+pub struct Day06;
+
+impl Solution for Day06 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 6;
+    const EXAMPLE: &'static str = include_str!("../examples/06-a.txt");
+
+    type Parsed<'a> = &'a [u8];
+    type Params = ();
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        Ok(input.as_bytes())
+    }
+
+    fn part1(bytes: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        let sequence = bytes
+            .windows(4)
+            .enumerate()
+            .find(|(_i, slice)| {
+                let set = slice.iter().collect::<HashSet<&u8>>();
+                slice.len() == set.len()
+            })
+            .ok_or_else(|| AocError::unsolvable("no 4 distinct characters in a row"))?;
+        Ok(sequence.0 + 1 + 3)
+    }
+
+    fn part2(bytes: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        let sequence = bytes
+            .windows(14)
+            .enumerate()
+            .find(|(_i, slice)| {
+                let set = slice.iter().collect::<HashSet<&u8>>();
+                slice.len() == set.len()
+            })
+            .ok_or_else(|| AocError::unsolvable("no 14 distinct characters in a row"))?;
+        Ok(sequence.0 + 1 + 13)
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day06::part1(&Day06::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
-pub fn process_part2(input: &str) -> String {
-    let bytes = input.bytes().collect::<Vec<u8>>();
-    let sequence = bytes
-        .windows(14)
-        .enumerate()
-        .find(|(_i, slice)| {
-            let set = slice.iter().collect::<HashSet<&u8>>();
-            slice.len() == set.len()
-        })
-        .unwrap();
-    (sequence.0 + 1 + 13).to_string()
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day06::part2(&Day06::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), "7");
-        assert_eq!(process_part1("bvwbjplbgvbhsrlpgdmjqwftvncz"), "5");
-        assert_eq!(process_part1("nppdvjthqldpwncqszvftbrmjl"), "6");
-        assert_eq!(process_part1("nznrnfrfntjfmvfwmzdfjlvtqnbhcprs"), "10");
-        assert_eq!(process_part1("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), "11");
-    }
-
-    #[test]
-    fn part2_works() {
-        assert_eq!(process_part2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), "19");
-        assert_eq!(process_part2("bvwbjplbgvbhsrlpgdmjqwftvncz"), "23");
-        assert_eq!(process_part2("nppdvjthqldpwncqszvftbrmjlhg"), "23");
-        assert_eq!(process_part2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), "29");
-        assert_eq!(process_part2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), "26");
-    }
+    aoc_common::example_tests!(Day06);
 }
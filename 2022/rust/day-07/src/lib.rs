@@ -1,4 +1,4 @@
-#![feature(iter_intersperse)]
+use aoc_common::{normalize, parse::parse_all, AocError, Result, Solution};
 use std::collections::BTreeMap;
 
 use nom::{
@@ -12,7 +12,7 @@ use nom::{
 
 enum Operation<'a> {
     Cd(Cd<'a>),
-    Ls(Vec<Files<'a>>),
+    Ls(Vec<Files>),
 }
 
 enum Cd<'a> {
@@ -21,34 +21,34 @@ enum Cd<'a> {
     Root,
 }
 
-enum Files<'a> {
-    File { size: u32, name: &'a str },
-    Dir(&'a str),
+enum Files {
+    File { size: u64 },
+    Dir,
 }
 
 fn file(input: &str) -> IResult<&str, Files> {
-    let (input, (size, name)) = separated_pair(
-        nom::character::complete::u32,
+    let (input, (size, _name)) = separated_pair(
+        nom::character::complete::u64,
         tag(" "),
         is_a("qwertyuiopasdfghjklzxcvbnm."),
     )(input)?;
-    Ok((input, Files::File { size, name }))
+    Ok((input, Files::File { size }))
 }
 
 fn directory(input: &str) -> IResult<&str, Files> {
     let (input, _) = tag("dir ")(input)?;
-    let (input, name) = alpha1(input)?;
-    Ok((input, Files::Dir(name)))
+    let (input, _name) = alpha1(input)?;
+    Ok((input, Files::Dir))
 }
 
-fn ls(input: &str) -> IResult<&str, Operation> {
+fn ls(input: &str) -> IResult<&str, Operation<'_>> {
     let (input, _) = tag("$ ls")(input)?;
     let (input, _) = newline(input)?;
     let (input, files) = separated_list1(newline, alt((file, directory)))(input)?;
     Ok((input, Operation::Ls(files)))
 }
 
-fn cd(input: &str) -> IResult<&str, Operation> {
+fn cd(input: &str) -> IResult<&str, Operation<'_>> {
     let (input, _) = tag("$ cd ")(input)?;
     let (input, dir) = alt((tag("/"), tag(".."), alpha1))(input)?;
     let op: Operation = match dir {
@@ -59,20 +59,22 @@ fn cd(input: &str) -> IResult<&str, Operation> {
     Ok((input, op))
 }
 
-fn commands(input: &str) -> IResult<&str, Vec<Operation>> {
+fn commands(input: &str) -> IResult<&str, Vec<Operation<'_>>> {
     let (input, cmd) = separated_list1(newline, alt((ls, cd)))(input)?;
     Ok((input, cmd))
 }
 
-struct File<'a> {
-    size: u32,
-    name: &'a str,
-}
-
 fn calculate_sizes<'a>(
-    (mut context, mut sizes): (Vec<&'a str>, BTreeMap<Vec<&'a str>, u32>),
-    command: &'a Operation,
-) -> (Vec<&'a str>, BTreeMap<Vec<&'a str>, u32>) {
+    (mut context, mut sizes): (Vec<&'a str>, DirectorySizes<'a>),
+    command: &Operation<'a>,
+) -> Result<(Vec<&'a str>, DirectorySizes<'a>)> {
+    let too_big = |context: &[&str]| {
+        AocError::unsolvable(format!(
+            "directory {}/ holds more than {} bytes",
+            context.join("/"),
+            u64::MAX
+        ))
+    };
     match command {
         Operation::Cd(Cd::Root) => {
             context.push("");
@@ -81,107 +83,118 @@ fn calculate_sizes<'a>(
             context.pop();
         }
         Operation::Cd(Cd::Down(name)) => {
-            context.push(name);
+            context.push(*name);
         }
         Operation::Ls(files) => {
             let sum = files
                 .iter()
                 .filter_map(|file| {
-                    if let Files::File { size, .. } = file {
-                        Some(size)
+                    if let Files::File { size } = file {
+                        Some(*size)
                     } else {
                         None
                     }
                 })
-                .sum::<u32>();
+                .try_fold(0u64, u64::checked_add)
+                .ok_or_else(|| too_big(&context))?;
 
             for i in 0..context.len() {
-                sizes
-                    .entry(context[0..=i].to_vec())
-                    .and_modify(|v| *v += sum)
-                    .or_insert(sum);
+                let path = &context[0..=i];
+                let size = sizes.entry(path.to_vec()).or_insert(0);
+                *size = size.checked_add(sum).ok_or_else(|| too_big(path))?;
             }
         }
     };
 
-    (context, sizes)
+    Ok((context, sizes))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let cmds = commands(input).unwrap().1;
+type DirectorySizes<'a> = BTreeMap<Vec<&'a str>, u64>;
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 7;
+    const EXAMPLE: &'static str = include_str!("../examples/07-a.txt");
+
+    type Parsed<'a> = DirectorySizes<'a>;
+    type Params = ();
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        // generate a tree of all directories and files as "cmds"
+        let cmds = parse_all(input, commands)?;
 
-    let (_, sizes) = cmds.iter().fold((vec![], BTreeMap::new()), calculate_sizes);
+        let (_, sizes) = cmds
+            .iter()
+            .try_fold((vec![], BTreeMap::new()), calculate_sizes)?;
+        Ok(sizes)
+    }
+
+    fn part1(sizes: &Self::Parsed<'_>, _params: &()) -> Result<u64> {
+        // take the sizes
+        // work with only the stored "size"
+        // remove the ones exceeding 100_000
+        // sum the rest
+        Ok(sizes.values().filter(|&&size| size < 100_000).sum::<u64>())
+    }
+
+    fn part2(sizes: &Self::Parsed<'_>, _params: &()) -> Result<u64> {
+        let total_size: u64 = 70_000_000;
+        let needed_space: u64 = 30_000_000;
+        let used_space = sizes
+            .get(&vec![""])
+            .ok_or_else(|| AocError::unsolvable("the root directory was never listed"))?;
+        let free_space = total_size
+            .checked_sub(*used_space)
+            .ok_or_else(|| AocError::unsolvable("the files do not fit on the disk"))?;
+        let at_least_this_much = needed_space.saturating_sub(free_space);
+
+        sizes
+            .values()
+            .filter(|&&size| size >= at_least_this_much)
+            .min()
+            .copied()
+            .ok_or_else(|| AocError::unsolvable("no directory frees up enough space"))
+    }
+}
 
-    sizes
-        .iter()
-        .map(|(_, size)| size)
-        .filter(|&&size| size < 100_000)
-        .sum::<u32>()
-        .to_string()
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day07::part1(&Day07::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
-pub fn process_part2(input: &str) -> String {
-    // generate a tree of all directories and files as "cmds"
-    let cmds = commands(input).unwrap().1;
-
-    let (_, sizes) = cmds.iter().fold((vec![], BTreeMap::new()), calculate_sizes);
-
-    let total_size = 70_000_000;
-    let needed_space = 30_000_000;
-    let used_space = sizes.get(&vec![""]).unwrap();
-    let free_space = total_size - *used_space;
-    let at_least_this_much = needed_space - free_space;
-
-    // take the sizes
-    // work with only the stored "size"
-    // remove the ones exceeding 100_000
-    // sum the rest
-    sizes
-        .iter()
-        .map(|(_, size)| size)
-        .filter(|&&size| size >= at_least_this_much)
-        .min()
-        .unwrap()
-        .to_string()
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day07::part2(&Day07::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
+    aoc_common::example_tests!(Day07);
 
     #[test]
-    fn part1_works() {
-        let result = process_part1(INPUT);
-        assert_eq!(result, "95437");
+    fn sizes_past_u32_do_not_overflow() -> Result<()> {
+        let input = "$ cd /\n$ ls\n4000000000 a\n4000000000 b\n";
+        assert_eq!(Day07::parse(input)?.get(&vec![""]), Some(&8_000_000_000));
+        assert_eq!(process_part1(input)?, "0");
+        Ok(())
     }
 
     #[test]
-    fn part2_works() {
-        let result = process_part2(INPUT);
-        assert_eq!(result, "24933642");
+    fn sizes_past_u64_are_an_error() {
+        let err = |input| Day07::parse(input).err().unwrap().to_string();
+        assert_eq!(
+            err("$ cd /\n$ ls\n18446744073709551615 a\n1 b"),
+            "no solution: directory / holds more than 18446744073709551615 bytes"
+        );
+        assert_eq!(
+            err("$ cd /\n$ ls\n18446744073709551615 a\ndir b\n$ cd b\n$ ls\n1 c"),
+            "no solution: directory / holds more than 18446744073709551615 bytes"
+        );
     }
 }
@@ -1,22 +1,12 @@
-use nom::{
-    character::complete::{anychar, newline},
-    combinator::verify,
-    multi::{many1, separated_list1},
-    *,
-};
-
-fn a_num(input: &str) -> IResult<&str, u32> {
-    let (input, c) = verify(anychar, |c| c.is_ascii_digit())(input)?;
-    let number = c.to_digit(10).unwrap();
-    Ok((input, number))
-}
+use aoc_common::{grid::Pos, normalize, Grid, Result, Solution};
+
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
 
-fn parse_trees(input: &str) -> IResult<&str, Vec<Vec<u32>>> {
-    let (input, vecs) = separated_list1(newline, many1(a_num))(input)?;
-    Ok((input, vecs))
+fn treeline(trees: &Grid<u32>, pos: Pos, direction: (isize, isize)) -> impl Iterator<Item = &u32> {
+    trees.ray(pos, direction).map(|pos| &trees[pos])
 }
 
-fn is_visible(cell: &u32, treeline: &[u32]) -> bool {
+fn is_visible<'a>(cell: &u32, treeline: impl Iterator<Item = &'a u32>) -> bool {
     for x in treeline {
         if x >= cell {
             return false;
@@ -26,34 +16,10 @@ fn is_visible(cell: &u32, treeline: &[u32]) -> bool {
     true
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, trees) = parse_trees(input).unwrap();
-    let (width, height) = (trees[0].len(), trees.len());
-    let mut num_visible = (width * 2) + (height * 2) - 4; // all edge trees are visible by default
-
-    for r in 1..height - 1 {
-        for c in 1..width - 1 {
-            let curr = &trees[r][c];
-            let east: Vec<u32> = trees[r][c + 1..].to_vec();
-            let south: Vec<u32> = trees[r + 1..].iter().map(|v| v[c]).collect();
-            let west: Vec<u32> = trees[r][..c].iter().cloned().rev().collect();
-            let north: Vec<u32> = trees[..r].iter().rev().map(|v| v[c]).collect();
-
-            let mut visibility = vec![false, false, false, false];
-            for (i, treeline) in [east, south, west, north].iter().enumerate() {
-                visibility[i] = is_visible(curr, treeline);
-            }
-
-            if visibility.iter().any(|&v| v) {
-                num_visible += 1;
-            }
-        }
-    }
-
-    num_visible.to_string()
-}
-
-fn calculate_directional_scenic_score(cell: &u32, treeline: &[u32]) -> u32 {
+fn calculate_directional_scenic_score<'a>(
+    cell: &u32,
+    treeline: impl Iterator<Item = &'a u32>,
+) -> u32 {
     let mut score: u32 = 0;
     for x in treeline {
         score += 1;
@@ -65,51 +31,109 @@ fn calculate_directional_scenic_score(cell: &u32, treeline: &[u32]) -> u32 {
     score
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, trees) = parse_trees(input).unwrap();
-    let mut high_score: u32 = 0;
-
-    for r in 1..(trees.len() - 1) {
-        for c in 1..(trees[0].len() - 1) {
-            let curr = &trees[r][c];
-            let east: Vec<u32> = trees[r][c + 1..].to_vec();
-            let south: Vec<u32> = trees[r + 1..].iter().map(|v| v[c]).collect();
-            let west: Vec<u32> = trees[r][..c].iter().cloned().rev().collect();
-            let north: Vec<u32> = trees[..r].iter().rev().map(|v| v[c]).collect();
-
-            let mut scores = vec![0, 0, 0, 0];
-            for (i, treeline) in [east, south, west, north].iter().enumerate() {
-                scores[i] = calculate_directional_scenic_score(curr, treeline);
-            }
+pub struct Day08;
 
-            let cell_score: u32 = scores.iter().product();
+impl Solution for Day08 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 8;
+    const EXAMPLE: &'static str = include_str!("../examples/08-a.txt");
 
-            if cell_score > high_score {
-                high_score = cell_score;
-            }
-        }
+    type Parsed<'a> = Grid<u32>;
+    type Params = ();
+    type Answer1 = usize;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        Grid::parse(input, |c| c.to_digit(10))
     }
 
-    high_score.to_string()
+    fn part1(trees: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        Ok(trees
+            .iter()
+            .filter(|&(pos, tree)| {
+                DIRECTIONS
+                    .iter()
+                    .any(|&direction| is_visible(tree, treeline(trees, pos, direction)))
+            })
+            .count())
+    }
+
+    fn part2(trees: &Self::Parsed<'_>, _params: &()) -> Result<u32> {
+        Ok(trees
+            .iter()
+            .map(|(pos, tree)| {
+                DIRECTIONS
+                    .iter()
+                    .map(|&direction| {
+                        calculate_directional_scenic_score(tree, treeline(trees, pos, direction))
+                    })
+                    .product()
+            })
+            .max()
+            .unwrap_or_default())
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day08::part1(&Day08::parse(&input)?, &()).map(|answer| answer.to_string())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day08::part2(&Day08::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::testing::{grid, proptest::char::range};
+
+    aoc_common::example_tests!(Day08);
+
+    /// The heights seen looking from `(x, y)` towards each edge, nearest
+    /// first.
+    fn sightlines(trees: &Grid<u32>, x: usize, y: usize) -> [Vec<u32>; 4] {
+        let (width, height) = (trees.width(), trees.height());
+        [
+            (x + 1..width).map(|x| trees[(x, y)]).collect(),
+            (y + 1..height).map(|y| trees[(x, y)]).collect(),
+            (0..x).rev().map(|x| trees[(x, y)]).collect(),
+            (0..y).rev().map(|y| trees[(x, y)]).collect(),
+        ]
+    }
 
-    const INPUT: &str = "30373
-25512
-65332
-33549
-35390";
+    fn naive_part1(trees: &Grid<u32>, _params: &()) -> Result<usize> {
+        let mut visible = 0;
+        for (x, y) in trees.positions() {
+            let tree = trees[(x, y)];
+            if sightlines(trees, x, y)
+                .iter()
+                .any(|line| line.iter().all(|&other| other < tree))
+            {
+                visible += 1;
+            }
+        }
+        Ok(visible)
+    }
 
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "21");
+    fn naive_part2(trees: &Grid<u32>, _params: &()) -> Result<u32> {
+        let mut best = 0;
+        for (x, y) in trees.positions() {
+            let tree = trees[(x, y)];
+            let mut score = 1;
+            for line in sightlines(trees, x, y) {
+                let blocked = line.iter().position(|&other| other >= tree);
+                score *= blocked.map_or(line.len(), |at| at + 1) as u32;
+            }
+            best = best.max(score);
+        }
+        Ok(best)
     }
 
-    #[test]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT), "8");
+    aoc_common::differential_tests! {
+        Day08;
+        part1_matches_naive: part1 => naive_part1, grid(range('0', '9'), 1..12, 1..12);
+        part2_matches_naive: part2 => naive_part2, grid(range('0', '9'), 1..12, 1..12);
     }
 }
@@ -1,225 +1,248 @@
-use itertools::Itertools;
-use lending_iterator::prelude::*;
+use aoc_common::{
+    normalize,
+    parse::{lines, parse_all},
+    tracing::trace,
+    viz::{Cell, Colour},
+    AocError, Direction, Frame, Part, Point2, Result, Solution, Viz,
+};
 use nom::{
-    branch::alt,
     bytes::complete::tag,
-    character::complete::{self, newline},
-    multi::separated_list1,
+    character::complete::{self, one_of},
+    combinator::map_opt,
     sequence::separated_pair,
     IResult,
 };
-use std::{cmp::Ordering, collections::HashSet};
+use std::collections::HashSet;
+
+type Pos = Point2<i32>;
 
-#[derive(Eq, PartialEq, Hash, Clone)]
-struct Pos {
-    x: i32,
-    y: i32,
+fn direction(input: &str) -> IResult<&str, Direction> {
+    map_opt(one_of("LRUD"), Direction::from_char)(input)
 }
 
-impl Pos {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
+fn moves(input: &str) -> IResult<&str, Vec<Move>> {
+    lines(separated_pair(direction, tag(" "), complete::u32))(input)
+}
 
-    fn zero() -> Self {
-        Self { x: 0, y: 0 }
-    }
+/// A direction and how many steps to take in it.
+type Move = (Direction, u32);
 
-    fn move_dir(&mut self, dir: Direction) {
-        match dir {
-            Direction::Left => {
-                self.x -= 1;
-            }
-            Direction::Right => {
-                self.x += 1;
-            }
-            Direction::Up => {
-                self.y += 1;
+/// Every move broken down into single steps.
+fn steps(move_set: &[Move]) -> impl Iterator<Item = Direction> + '_ {
+    move_set
+        .iter()
+        .flat_map(|&(dir, repeat)| std::iter::repeat_n(dir, repeat as usize))
+}
+
+fn step_count(move_set: &[Move]) -> usize {
+    move_set.iter().map(|&(_, repeat)| repeat as usize).sum()
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 9;
+    const EXAMPLE: &'static str = include_str!("../examples/09-a.txt");
+
+    type Parsed<'a> = Vec<Move>;
+    type Params = ();
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        let move_set = parse_all(input, moves)?;
+        // Every knot stays between the head's extremes, so bounding the head
+        // keeps the whole rope inside an `i32`.
+        let mut head = Point2::<i64>::default();
+        for (line, &(dir, repeat)) in input.lines().zip(&move_set) {
+            let repeat = i64::from(repeat);
+            match dir {
+                Direction::Up => head.y -= repeat,
+                Direction::Right => head.x += repeat,
+                Direction::Down => head.y += repeat,
+                Direction::Left => head.x -= repeat,
             }
-            Direction::Down => {
-                self.y -= 1;
+            if i32::try_from(head.x).is_err() || i32::try_from(head.y).is_err() {
+                return Err(AocError::parse(
+                    input,
+                    line,
+                    "moves the head further than an i32 can reach",
+                ));
             }
-        };
+        }
+        Ok(move_set)
     }
-}
 
-impl std::fmt::Debug for Pos {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Point ({}, {})", self.x, self.y)
+    fn part1(move_set: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        pull_short_rope(move_set, &mut Viz::off())
     }
-}
-
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
 
-fn direction(input: &str) -> IResult<&str, Direction> {
-    let (input, c) = alt((
-        complete::char('L'),
-        complete::char('R'),
-        complete::char('U'),
-        complete::char('D'),
-    ))(input)?;
-
-    let dir = match c {
-        'L' => Direction::Left,
-        'R' => Direction::Right,
-        'U' => Direction::Up,
-        'D' => Direction::Down,
-        _ => unreachable!(),
-    };
+    fn part2(move_set: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        pull_long_rope(move_set, &mut Viz::off())
+    }
 
-    Ok((input, dir))
+    fn visualize(
+        move_set: &Self::Parsed<'_>,
+        _params: &(),
+        part: Part,
+        viz: &mut Viz,
+    ) -> Result<String> {
+        match part {
+            Part::One => pull_short_rope(move_set, viz),
+            Part::Two => pull_long_rope(move_set, viz),
+        }
+        .map(|answer| answer.to_string())
+    }
 }
 
-fn moves(input: &str) -> IResult<&str, Vec<Direction>> {
-    let (input, vecs) =
-        separated_list1(newline, separated_pair(direction, tag(" "), complete::u32))(input)?;
-
-    let vecs = vecs
-        .iter()
-        .flat_map(|(dir, repeat)| vec![*dir; *repeat as usize])
-        .collect();
-
-    Ok((input, vecs))
+/// The corners of the rectangle the head moves around in. The rest of the
+/// rope follows it, so never leaves it either.
+fn head_bounds(move_set: &[Move]) -> (Pos, Pos) {
+    let mut head = Pos::default();
+    let (mut top_left, mut bottom_right) = (head, head);
+    for dir in steps(move_set) {
+        head = head.step(dir);
+        top_left = Pos::new(top_left.x.min(head.x), top_left.y.min(head.y));
+        bottom_right = Pos::new(bottom_right.x.max(head.x), bottom_right.y.max(head.y));
+    }
+    (top_left, bottom_right)
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, move_set) = moves(input).unwrap();
-    let mut head = Pos::zero();
-    let mut tail = Pos::zero();
-    let mut tail_positions = HashSet::from([tail.clone()]);
+/// The rope after `step` moves, head highlighted, over the places its tail
+/// has been.
+fn rope_frame(
+    (top_left, bottom_right): (Pos, Pos),
+    step: usize,
+    rope: &[Pos],
+    visited: &HashSet<Pos>,
+) -> Frame {
+    let trail = visited.iter().map(|&pos| (pos, Cell::from('#')));
+    let start = (Pos::default(), Cell::from('s'));
+    let knots = rope.iter().enumerate().rev().map(|(knot, &pos)| {
+        let cell = match knot {
+            0 => Cell::new('H', Colour::Red),
+            _ if rope.len() == 2 => Cell::new('T', Colour::Green),
+            knot => Cell::from(char::from_digit(knot as u32, 10).unwrap_or('*')),
+        };
+        (pos, cell)
+    });
+    Frame::from_points_in(
+        top_left,
+        bottom_right,
+        trail.chain([start]).chain(knots),
+        '.',
+    )
+    .caption(format!("move {step}, tail visited {}", visited.len()))
+}
 
-    for dir in move_set.iter() {
-        head.move_dir(*dir);
-        let x_range = (&head.x - 1)..=(&head.x + 1);
-        let y_range = (&head.y - 1)..=(&head.y + 1);
+fn pull_short_rope(move_set: &[Move], viz: &mut Viz) -> Result<usize> {
+    let mut head = Pos::default();
+    let mut tail = Pos::default();
+    let mut tail_positions = HashSet::from([tail]);
+    let bounds = if viz.is_on() {
+        head_bounds(move_set)
+    } else {
+        Default::default()
+    };
 
-        let tail_is_connected = x_range
-            .cartesian_product(y_range)
-            .any(|(x, y)| Pos::new(x, y) == tail);
+    for (step, dir) in steps(move_set).enumerate() {
+        head = head.step(dir);
+        let tail_is_connected = head.chebyshev(&tail) <= 1;
 
         if !tail_is_connected {
-            let mut new_tail = head.clone();
-            match dir {
-                Direction::Left => new_tail.move_dir(Direction::Right),
-                Direction::Right => new_tail.move_dir(Direction::Left),
-                Direction::Up => new_tail.move_dir(Direction::Down),
-                Direction::Down => new_tail.move_dir(Direction::Up),
-            };
-            tail = new_tail.clone();
-            tail_positions.insert(new_tail);
+            tail = head.step(dir.opposite());
+            tail_positions.insert(tail);
         }
+        viz.frame(|| rope_frame(bounds, step + 1, &[head, tail], &tail_positions));
     }
+    viz.last_frame(|| rope_frame(bounds, step_count(move_set), &[head, tail], &tail_positions));
 
-    tail_positions.len().to_string()
+    Ok(tail_positions.len())
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, move_set) = moves(input).unwrap();
-    let mut rope: Vec<Pos> = vec![Pos::zero(); 10];
-    let mut tail_positions = HashSet::from([rope.last().unwrap().clone()]);
-
-    for dir in move_set.iter() {
-        rope[0].move_dir(*dir);
-        let mut rope_windows = rope.windows_mut::<2>();
-        // let mut last_head_move = dir.clone();
-        while let Some([ref mut head, ref mut tail]) = rope_windows.next() {
-            let x_range = (head.x - 1)..=(head.x + 1);
-            let y_range = (head.y - 1)..=(head.y + 1);
-            let head_cross_positions: Vec<Pos> = x_range
-                .clone()
-                .cartesian_product(y_range.clone())
-                .map(|(x, y)| Pos::new(x, y))
-                .collect();
-            let follow_is_connected = head_cross_positions.clone().iter().any(|pos| *pos == *tail);
-
-            if !follow_is_connected {
-                // println!("{last_head_move:?}");
-                // let mut new_follow = head.clone();
-                if head.x == tail.x {
-                    match head.y.cmp(&tail.y) {
-                        Ordering::Greater => tail.y += 1,
-                        Ordering::Less => tail.y -= 1,
-                        Ordering::Equal => {}
-                    };
-                } else if head.y == tail.y {
-                    match head.x.cmp(&tail.x) {
-                        Ordering::Greater => tail.x += 1,
-                        Ordering::Less => tail.x -= 1,
-                        Ordering::Equal => {}
-                    }
-                } else {
-                    // let head_cross_positions = [
-                    //     (head.x - 1, head.y),
-                    //     (head.x + 1, head.y),
-                    //     (head.x, head.y - 1),
-                    //     (head.x, head.y + 1),
-                    // ];
-
-                    let head_cross_positions =
-                        x_range.cartesian_product(y_range).collect::<Vec<_>>();
-                    let x_range = (tail.x - 1)..=(tail.x + 1);
-                    let y_range = (tail.y - 1)..=(tail.y + 1);
-
-                    let maybe_new_tail: Vec<Pos> = x_range
-                        .cartesian_product(y_range)
-                        .filter(|tuple| head_cross_positions.contains(tuple))
-                        .map(|(x, y)| Pos::new(x, y))
-                        .collect();
-                    match maybe_new_tail.len() {
-                        2 => {}
-                        1 => {
-                            *tail = maybe_new_tail[0];
-                        }
-                        _ => {
-                            panic!("Unknown Tail Length")
-                        }
-                    }
-                    // *tail = new_tail;
-                }
+fn pull_long_rope(move_set: &[Move], viz: &mut Viz) -> Result<usize> {
+    let mut rope: Vec<Pos> = vec![Pos::default(); 10];
+    let mut tail_positions = HashSet::from([rope[rope.len() - 1]]);
+    let bounds = if viz.is_on() {
+        head_bounds(move_set)
+    } else {
+        Default::default()
+    };
+
+    for (step, dir) in steps(move_set).enumerate() {
+        rope[0] = rope[0].step(dir);
+        for knot in 1..rope.len() {
+            let (front, back) = rope.split_at_mut(knot);
+            let (head, tail) = (&front[knot - 1], &mut back[0]);
+            if head.chebyshev(tail) > 1 {
+                tail.x += (head.x - tail.x).signum();
+                tail.y += (head.y - tail.y).signum();
             }
         }
-        tail_positions.insert(rope.last().unwrap().clone());
-        dbg!(dir, &rope);
+        tail_positions.insert(rope[rope.len() - 1]);
+        trace!(step, ?dir, ?rope, "moved");
+        viz.frame(|| rope_frame(bounds, step + 1, &rope, &tail_positions));
     }
+    viz.last_frame(|| rope_frame(bounds, step_count(move_set), &rope, &tail_positions));
+
+    Ok(tail_positions.len())
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day09::part1(&Day09::parse(&input)?, &()).map(|answer| answer.to_string())
+}
 
-    tail_positions.len().to_string()
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day09::part2(&Day09::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const INPUT_1: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
-
-    const INPUT_2: &str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20";
+    use aoc_common::testing::rope_moves;
+
+    aoc_common::example_tests!(Day09);
+
+    /// Moves the tail one step towards the head on each axis whenever they
+    /// stop touching.
+    fn follow_part1(moves: &[Move], _params: &()) -> Result<usize> {
+        let (mut head, mut tail) = (Pos::default(), Pos::default());
+        let mut visited = HashSet::from([tail]);
+        for dir in steps(moves) {
+            head = head.step(dir);
+            if head.chebyshev(&tail) > 1 {
+                tail.x += (head.x - tail.x).signum();
+                tail.y += (head.y - tail.y).signum();
+                visited.insert(tail);
+            }
+        }
+        Ok(visited.len())
+    }
 
     #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT_1), "13");
+    fn long_moves_parse_without_expanding() -> Result<()> {
+        let moves = Day09::parse("R 2000000000\nL 4000000000")?;
+        assert_eq!(
+            moves,
+            [
+                (Direction::Right, 2_000_000_000),
+                (Direction::Left, 4_000_000_000)
+            ]
+        );
+        assert_eq!(step_count(&moves), 6_000_000_000);
+
+        assert_eq!(
+            Day09::parse("U 3\nR 4000000000").err().unwrap().to_string(),
+            "moves the head further than an i32 can reach at line 2, column 1: `R 4000000000`"
+        );
+        Ok(())
     }
 
-    #[test]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT_2), "36");
+    aoc_common::differential_tests! {
+        Day09;
+        part1_matches_follow: part1 => follow_part1, rope_moves(1..=6, 1..40);
     }
 }
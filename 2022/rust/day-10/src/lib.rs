@@ -1,15 +1,15 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{self, newline},
-    multi::separated_list1,
-    sequence::preceded,
-    *,
+use aoc_common::{
+    normalize,
+    parse::{lines, parse_all},
+    tracing::trace,
+    viz::Colour,
+    AocError, Frame, Grid, Part, Result, Solution, Viz,
 };
+use nom::{branch::alt, bytes::complete::tag, character::complete, sequence::preceded, *};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq)]
-enum Operation {
+pub enum Operation {
     Add(i32),
     Noop,
 }
@@ -26,66 +26,121 @@ fn noop(input: &str) -> IResult<&str, Operation> {
 }
 
 fn operations(input: &str) -> IResult<&str, Vec<Operation>> {
-    let (input, ops) = separated_list1(newline, alt((addx, noop)))(input)?;
+    let (input, ops) = lines(alt((addx, noop)))(input)?;
     Ok((input, ops))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, operations) = operations(input).unwrap();
-    let key_cycles = [20, 60, 100, 140, 180, 220];
-    let mut key_cycle_values: HashMap<i32, i32> = HashMap::new();
-    let mut register: i32 = 1;
+fn draw(crt_display: &mut Grid<char>, cycle: &i32, register: &i32) {
+    let pixels = register.saturating_sub(1)..=register.saturating_add(1);
+    let row = (cycle / 40) as usize;
+    let col = (cycle % 40) as usize;
+    let pixel = match pixels.contains(&(col as i32)) {
+        true => '#',
+        false => '.',
+    };
+    trace!(cycle, register, row, col, %pixel, "drew");
+    crt_display[(col, row)] = pixel;
+}
 
-    let mut cycle: i32 = 0;
-    for op in operations {
-        cycle += 1;
-        if key_cycles.contains(&cycle) {
-            key_cycle_values.insert(cycle, register);
-        }
-        if let Add(num) = op {
+/// The screen as drawn so far, with the sprite on the current row and the
+/// pixel just drawn highlighted.
+fn crt_frame(crt_display: &Grid<char>, cycle: i32, register: i32) -> Frame {
+    let row = (cycle / 40) as usize;
+    let sprite = (register.saturating_sub(1)..=register.saturating_add(1))
+        .filter_map(|col| usize::try_from(col).ok())
+        .filter(|&col| col < crt_display.width());
+    sprite
+        .fold(Frame::new(crt_display), |frame, col| {
+            frame.highlight((col, row), Colour::Blue)
+        })
+        .highlight(((cycle % 40) as usize, row), Colour::Yellow)
+        .caption(format!("cycle {}, X = {register}", cycle + 1))
+}
+
+/// Adds `x` to the register, which only holds an `i32`.
+fn add_to_register(register: i32, x: i32) -> Result<i32> {
+    register
+        .checked_add(x)
+        .ok_or_else(|| AocError::unsolvable(format!("X overflows adding {x} to {register}")))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 10;
+    const EXAMPLE: &'static str = include_str!("../examples/10-a.txt");
+
+    type Parsed<'a> = Vec<Operation>;
+    type Params = ();
+    type Answer1 = i32;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        parse_all(input, operations)
+    }
+
+    fn part1(operations: &Self::Parsed<'_>, _params: &()) -> Result<i32> {
+        let key_cycles = [20, 60, 100, 140, 180, 220];
+        let mut key_cycle_values: HashMap<i32, i32> = HashMap::new();
+        let mut register: i32 = 1;
+
+        let mut cycle: i32 = 0;
+        for op in operations {
             cycle += 1;
             if key_cycles.contains(&cycle) {
                 key_cycle_values.insert(cycle, register);
             }
-            register += num;
-        }
-        if cycle > 220 {
-            break;
+            if let Add(num) = op {
+                cycle += 1;
+                if key_cycles.contains(&cycle) {
+                    key_cycle_values.insert(cycle, register);
+                }
+                register = add_to_register(register, *num)?;
+            }
+            if cycle > 220 {
+                break;
+            }
         }
+
+        key_cycle_values
+            .iter()
+            .try_fold(0i32, |sum, (cycle, register)| {
+                cycle.checked_mul(*register)?.checked_add(sum)
+            })
+            .ok_or_else(|| AocError::unsolvable("the signal strengths overflow an i32"))
     }
 
-    key_cycle_values
-        .iter()
-        .map(|(cycle, register)| cycle * register)
-        .sum::<i32>()
-        .to_string()
-}
+    fn part2(operations: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        run_crt(operations, &mut Viz::off())
+    }
 
-fn draw(crt_display: &mut [Vec<char>], cycle: &i32, register: &i32) {
-    let pixels = (register - 1)..=(register + 1);
-    let row = (cycle / 40) as usize;
-    let col = (cycle % 40) as usize;
-    dbg!(cycle, register, row, col);
-    crt_display[row].push(match pixels.contains(&(col as i32)) {
-        true => dbg!('#'),
-        false => dbg!('.'),
-    });
+    fn visualize(
+        operations: &Self::Parsed<'_>,
+        params: &(),
+        part: Part,
+        viz: &mut Viz,
+    ) -> Result<String> {
+        match part {
+            Part::One => Self::part1(operations, params).map(|answer| answer.to_string()),
+            Part::Two => run_crt(operations, viz),
+        }
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    let mut crt_display: Vec<Vec<char>> = Vec::with_capacity(6);
-    for _ in 0..6 {
-        crt_display.push(Vec::with_capacity(40));
-    }
-    let operations = operations(input).unwrap().1;
-    let mut ops_iter = operations.into_iter().peekable();
+fn run_crt(operations: &[Operation], viz: &mut Viz) -> Result<String> {
+    let mut crt_display = Grid::new(40, 6, '.');
+    let mut ops_iter = operations.iter().cloned().peekable();
     let mut register: i32 = 1;
     let mut cycle = 0;
-    let mut curr_op = ops_iter.next().unwrap();
+    let mut curr_op = ops_iter
+        .next()
+        .ok_or_else(|| AocError::unsolvable("there are no instructions"))?;
     let mut second_cycle = false;
 
     while cycle < 240 {
         draw(&mut crt_display, &cycle, &register);
+        viz.frame(|| crt_frame(&crt_display, cycle, register));
 
         cycle += 1;
         if let Noop = curr_op {
@@ -94,7 +149,7 @@ pub fn process_part2(input: &str) -> String {
             }
         } else if second_cycle {
             let Add(x) = curr_op else { unreachable!() };
-            register += x;
+            register = add_to_register(register, x)?;
             if let Some(new_op) = ops_iter.next() {
                 curr_op = new_op;
             }
@@ -103,182 +158,38 @@ pub fn process_part2(input: &str) -> String {
             second_cycle = true;
         }
     }
+    viz.last_frame(|| crt_frame(&crt_display, cycle - 1, register));
+
+    Ok(crt_display.to_string())
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day10::part1(&Day10::parse(&input)?, &()).map(|answer| answer.to_string())
+}
 
-    crt_display
-        .iter()
-        .map(|row| row.iter().collect::<String>())
-        .fold(String::new(), |acc, elem| acc + &elem + "\n")
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day10::part2(&Day10::parse(&input)?, &())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop";
-
-    const OUTPUT: &str = "##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######.....
-";
-
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "13140");
-    }
+    aoc_common::example_tests!(Day10);
 
     #[test]
-    // #[ignore]
-    fn part2_works() {
-        println!("{}", process_part2(INPUT));
-        println!("{}", OUTPUT);
-        assert_eq!(process_part2(INPUT), OUTPUT.to_string());
+    fn reports_overflowing_registers() {
+        let err = |input: &str| process_part1(input).unwrap_err().to_string();
+        assert_eq!(
+            err("addx 2147483647\naddx 1"),
+            "no solution: X overflows adding 2147483647 to 1"
+        );
+        assert_eq!(
+            err(&format!("addx 2147483646\n{}", "noop\n".repeat(20))),
+            "no solution: the signal strengths overflow an i32"
+        );
+        assert!(process_part2("addx 2147483647\naddx 1").is_err());
     }
 }
@@ -1,9 +1,14 @@
+use aoc_common::{
+    normalize,
+    parse::{groups, labelled, numbers, parse_all},
+    AocError, Result, Solution,
+};
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{self, multispace1},
-    multi::separated_list1,
-    sequence::{delimited, preceded},
+    combinator::verify,
+    sequence::{delimited, preceded, terminated},
     *,
 };
 use std::collections::VecDeque;
@@ -28,7 +33,7 @@ struct Test {
 }
 
 #[derive(Debug, Clone)]
-struct Monkey {
+pub struct Monkey {
     items: VecDeque<u128>,
     operation: Operation,
     test: Test,
@@ -36,39 +41,29 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn inspect(&mut self, magic_number: u128) -> u128 {
+    /// The worry level of the next item once this monkey, the `index`th, has
+    /// looked at it, kept below `magic_number`.
+    fn inspect(&mut self, index: usize, magic_number: u128) -> Result<u128> {
         self.touch_count += 1;
         let item = self.items.pop_front().unwrap();
+        let value = |value: &Value| match value {
+            Value::Old => item,
+            Value::Num(num) => *num as u128,
+        };
         let result = match &self.operation {
-            Operation::Mult((a, b)) => {
-                let num_a = match a {
-                    Value::Old => item,
-                    Value::Num(num) => *num as u128,
-                };
-                let num_b = match b {
-                    Value::Old => item,
-                    Value::Num(num) => *num as u128,
-                };
-                num_a * num_b
-            }
-            Operation::Add((a, b)) => {
-                let num_a = match a {
-                    Value::Old => item,
-                    Value::Num(num) => *num as u128,
-                };
-                let num_b = match b {
-                    Value::Old => item,
-                    Value::Num(num) => *num as u128,
-                };
-                num_a + num_b
-            }
+            Operation::Mult((a, b)) => value(a).checked_mul(value(b)),
+            Operation::Add((a, b)) => value(a).checked_add(value(b)),
         };
 
-        result % magic_number
+        result.map(|result| result % magic_number).ok_or_else(|| {
+            AocError::unsolvable(format!(
+                "monkey {index} makes a worry level of {item} overflow"
+            ))
+        })
     }
 
     fn test(&self, item: u128) -> usize {
-        if item % (self.test.divisible as u128) == 0 {
+        if item.is_multiple_of(self.test.divisible as u128) {
             self.test.if_true as usize
         } else {
             self.test.if_false as usize
@@ -77,38 +72,49 @@ impl Monkey {
 }
 
 fn items(input: &str) -> IResult<&str, VecDeque<u128>> {
-    let (input, items) = preceded(
-        tag("Starting items: "),
-        separated_list1(tag(", "), complete::u128),
-    )(input)?;
+    let (input, items) = labelled("Starting items", numbers(tag(", ")))(input)?;
 
     Ok((input, VecDeque::from(items)))
 }
 
 fn value(input: &str) -> IResult<&str, Value> {
-    alt((tag("old").map(|_| Value::Old), complete::u64.map(Value::Num)))(input)
+    alt((
+        tag("old").map(|_| Value::Old),
+        complete::u64.map(Value::Num),
+    ))(input)
 }
 
 fn operation(input: &str) -> IResult<&str, Operation> {
-    let (input, value_1) = preceded(tag("Operation: new = "), value)(input)?;
+    let (input, value_1) = labelled("Operation", preceded(tag("new = "), value))(input)?;
     let (input, operator) = delimited(multispace1, alt((tag("*"), tag("+"))), multispace1)(input)?;
     let (input, value_2) = value(input)?;
 
     let result = match operator {
         "*" => Operation::Mult((value_1, value_2)),
         "+" => Operation::Add((value_1, value_2)),
-        _ => panic!("unknown operator"),
+        _ => unreachable!(),
     };
 
     Ok((input, result))
 }
 
 fn test(input: &str) -> IResult<&str, Test> {
-    let (input, divisible) =
-        delimited(tag("Test: divisible by "), complete::u64, multispace1)(input)?;
-    let (input, if_true) =
-        delimited(tag("If true: throw to monkey "), complete::u64, multispace1)(input)?;
-    let (input, if_false) = preceded(tag("If false: throw to monkey "), complete::u64)(input)?;
+    let (input, divisible) = terminated(
+        labelled(
+            "Test",
+            preceded(
+                tag("divisible by "),
+                verify(complete::u64, |divisible| *divisible > 0),
+            ),
+        ),
+        multispace1,
+    )(input)?;
+    let (input, if_true) = terminated(
+        labelled("If true", preceded(tag("throw to monkey "), complete::u64)),
+        multispace1,
+    )(input)?;
+    let (input, if_false) =
+        labelled("If false", preceded(tag("throw to monkey "), complete::u64))(input)?;
 
     let result = Test {
         divisible,
@@ -139,118 +145,159 @@ fn monkey(input: &str) -> IResult<&str, Monkey> {
     ))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_input, mut monkeys) = separated_list1(tag("\n\n"), monkey)(input).unwrap();
-    let num_monkeys = monkeys.len();
-    let magic_number: u128 = monkeys
+/// The product of every monkey's divisor. Worry levels can be kept below it
+/// without changing which monkey any item is thrown to.
+fn magic_number(monkeys: &[Monkey]) -> Result<u128> {
+    monkeys
         .iter()
-        .map(|monkey| monkey.test.divisible)
-        .product::<u64>()
-        .into();
-
-    for _round in 0..20 {
-        // 20 rounds
-        for monkey_index in 0..num_monkeys {
-            for _ in 0..monkeys[monkey_index].items.len() {
-                let monkey = monkeys.get_mut(monkey_index).unwrap();
-                let mut item = monkey.inspect(magic_number);
-                item /= 3;
-                let destination_monkey = monkey.test(item);
-                monkeys
-                    .get_mut(destination_monkey)
-                    .unwrap()
-                    .items
-                    .push_back(item);
+        .enumerate()
+        .try_fold(1u128, |product, (index, monkey)| {
+            product
+                .checked_mul(monkey.test.divisible.into())
+                .ok_or_else(|| {
+                    AocError::unsolvable(format!(
+                        "the divisors overflow at monkey {index}'s {}",
+                        monkey.test.divisible
+                    ))
+                })
+        })
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 11;
+    const EXAMPLE: &'static str = include_str!("../examples/11-a.txt");
+
+    type Parsed<'a> = Vec<Monkey>;
+    type Params = ();
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        parse_all(input, groups(monkey))
+    }
+
+    fn part1(monkeys: &Self::Parsed<'_>, _params: &()) -> Result<u64> {
+        let mut monkeys = monkeys.clone();
+        let num_monkeys = monkeys.len();
+        let magic_number = magic_number(&monkeys)?;
+
+        for _round in 0..20 {
+            // 20 rounds
+            for monkey_index in 0..num_monkeys {
+                for _ in 0..monkeys[monkey_index].items.len() {
+                    let monkey = monkeys.get_mut(monkey_index).unwrap();
+                    let mut item = monkey.inspect(monkey_index, magic_number)?;
+                    item /= 3;
+                    let destination_monkey = monkey.test(item);
+                    monkeys
+                        .get_mut(destination_monkey)
+                        .ok_or_else(|| {
+                            AocError::unsolvable(format!(
+                                "monkey {monkey_index} throws to missing monkey {destination_monkey}"
+                            ))
+                        })?
+                        .items
+                        .push_back(item);
+                }
             }
         }
-    }
 
-    monkeys.sort_by_key(|monkey| monkey.touch_count);
-    let monkey_business: u64 = monkeys
-        .iter()
-        .map(|monkey| monkey.touch_count)
-        .rev()
-        .take(2)
-        .product();
+        monkeys.sort_by_key(|monkey| monkey.touch_count);
+        let monkey_business: u64 = monkeys
+            .iter()
+            .map(|monkey| monkey.touch_count)
+            .rev()
+            .take(2)
+            .product();
 
-    monkey_business.to_string()
-}
+        Ok(monkey_business)
+    }
 
-pub fn process_part2(input: &str) -> String {
-    let (_input, mut monkeys) = separated_list1(tag("\n\n"), monkey)(input).unwrap();
-    let num_monkeys = monkeys.len();
-    let magic_number: u128 = monkeys
-        .iter()
-        .map(|monkey| monkey.test.divisible)
-        .product::<u64>()
-        .into();
-
-    for _round in 0..10_000 {
-        // 20 rounds
-        for monkey_index in 0..num_monkeys {
-            for _ in 0..monkeys[monkey_index].items.len() {
-                let monkey = monkeys.get_mut(monkey_index).unwrap();
-                let item = monkey.inspect(magic_number);
-                let destination_monkey = monkey.test(item);
-                monkeys
-                    .get_mut(destination_monkey)
-                    .unwrap()
-                    .items
-                    .push_back(item);
+    fn part2(monkeys: &Self::Parsed<'_>, _params: &()) -> Result<u64> {
+        let mut monkeys = monkeys.clone();
+        let num_monkeys = monkeys.len();
+        let magic_number = magic_number(&monkeys)?;
+
+        for _round in 0..10_000 {
+            // 20 rounds
+            for monkey_index in 0..num_monkeys {
+                for _ in 0..monkeys[monkey_index].items.len() {
+                    let monkey = monkeys.get_mut(monkey_index).unwrap();
+                    let item = monkey.inspect(monkey_index, magic_number)?;
+                    let destination_monkey = monkey.test(item);
+                    monkeys
+                        .get_mut(destination_monkey)
+                        .ok_or_else(|| {
+                            AocError::unsolvable(format!(
+                                "monkey {monkey_index} throws to missing monkey {destination_monkey}"
+                            ))
+                        })?
+                        .items
+                        .push_back(item);
+                }
             }
         }
+
+        monkeys.sort_by_key(|monkey| monkey.touch_count);
+        let monkey_business: u64 = monkeys
+            .iter()
+            .map(|monkey| monkey.touch_count)
+            .rev()
+            .take(2)
+            .product();
+
+        Ok(monkey_business)
     }
+}
 
-    monkeys.sort_by_key(|monkey| monkey.touch_count);
-    let monkey_business: u64 = monkeys
-        .iter()
-        .map(|monkey| monkey.touch_count)
-        .rev()
-        .take(2)
-        .product();
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day11::part1(&Day11::parse(&input)?, &()).map(|answer| answer.to_string())
+}
 
-    monkey_business.to_string()
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day11::part2(&Day11::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
+    aoc_common::example_tests!(Day11);
 
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "10605");
+    fn monkey(items: &str, operation: &str, divisor: u64) -> String {
+        format!(
+            "Monkey 0:\n  Starting items: {items}\n  Operation: new = {operation}\n  \
+             Test: divisible by {divisor}\n    If true: throw to monkey 1\n    \
+             If false: throw to monkey 1"
+        )
     }
 
     #[test]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT), "2713310158");
+    fn reports_overflowing_worry_levels() {
+        let err = |monkeys: &[String]| process_part2(&monkeys.join("\n\n")).unwrap_err();
+        let huge = u64::MAX - 1;
+        assert_eq!(
+            err(&[
+                monkey("1", "old * 2", huge),
+                monkey("1", "old * 2", huge),
+                monkey("1", "old * 2", huge),
+            ])
+            .to_string(),
+            format!("no solution: the divisors overflow at monkey 2's {huge}")
+        );
+        let item = u128::MAX;
+        assert_eq!(
+            err(&[
+                monkey("1", "old + 1", 2),
+                monkey(&item.to_string(), "old + 1", 3)
+            ])
+            .to_string(),
+            format!("no solution: monkey 1 makes a worry level of {item} overflow")
+        );
     }
 }
@@ -1,159 +1,140 @@
-use nom::{
-    character::complete::{alpha1, newline},
-    multi::separated_list1,
-    IResult, Parser,
+use aoc_common::{
+    grid::Pos, normalize, viz::Colour, AocError, Frame, Grid, Part, Point2, Result, Solution, Viz,
 };
 use pathfinding::prelude::astar;
 
-type Grid = Vec<Vec<i32>>;
+fn distance(a: &Pos, b: &Pos) -> u32 {
+    Point2::from(*a).manhattan(&Point2::from(*b)) as u32
+}
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-struct Pos(usize, usize);
+fn successors(grid: &Grid<i32>, pos: &Pos) -> Vec<(Pos, u32)> {
+    grid.neighbors4(*pos)
+        .filter(|next| grid[*next] - grid[*pos] <= 1)
+        .map(|next| (next, 1))
+        .collect()
+}
 
-impl Pos {
-    fn new() -> Pos {
-        Pos(0, 0)
+fn height(c: char) -> i32 {
+    match c {
+        'S' => height('a'),
+        'E' => height('z'),
+        _ => c as i32 - 96,
     }
+}
 
-    fn distance(&self, other: &Pos) -> u32 {
-        (self.0.abs_diff(other.0) + self.1.abs_diff(other.1)) as u32
+pub struct Day12;
+
+impl Solution for Day12 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 12;
+    const EXAMPLE: &'static str = include_str!("../examples/12-a.txt");
+
+    type Parsed<'a> = (Grid<i32>, Pos, Pos);
+    type Params = ();
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        let squares = Grid::parse(input, |c| matches!(c, 'a'..='z' | 'S' | 'E').then_some(c))?;
+
+        let end_of_input = &input[input.len()..];
+        let start = squares
+            .find(&'S')
+            .ok_or_else(|| AocError::parse(input, end_of_input, "expected an `S`"))?;
+        let end = squares
+            .find(&'E')
+            .ok_or_else(|| AocError::parse(input, end_of_input, "expected an `E`"))?;
+        Ok((squares.map(|c| height(*c)), start, end))
     }
 
-    fn successors(&self, grid: &Grid) -> Vec<(Pos, u32)> {
-        let &Pos(this_r, this_c) = self;
-        [
-            this_r.checked_sub(1).map(|diff| Pos(diff, this_c)),
-            Some(Pos(this_r + 1, this_c)),
-            this_c.checked_sub(1).map(|diff| Pos(this_r, diff)),
-            Some(Pos(this_r, this_c + 1)),
-        ]
-        .into_iter()
-        .flatten()
-        .filter(|Pos(r, c)| {
-            *r < grid.len() && *c < grid[0].len() && grid[*r][*c] - grid[this_r][this_c] <= 1
-        })
-        .map(|p| (p, 1))
-        .collect()
+    fn part1((grid, start, end): &Self::Parsed<'_>, _params: &()) -> Result<u32> {
+        climb(grid, start, end)
+            .map(|(_path, dist)| dist)
+            .ok_or_else(|| AocError::unsolvable("there is no path from `S` to `E`"))
     }
-}
 
-fn grid_start_end(input: &str) -> IResult<&str, (Grid, Pos, Pos)> {
-    let (_, mut grid) = separated_list1(
-        newline,
-        alpha1.map(|chars: &str| {
-            chars
-                .chars()
-                .map(|c| match c {
-                    'S' => 0,
-                    'E' => -1,
-                    _ => c as i32 - 96,
-                })
-                .collect::<Vec<i32>>()
-        }),
-    )(input)?;
-
-    let mut start = Pos::new();
-    let mut end = Pos::new();
-    for (r, row) in grid.iter_mut().enumerate() {
-        for (c, col) in row.iter_mut().enumerate() {
-            if *col == 0 {
-                start = Pos(r, c);
-                *col = 1;
-            } else if *col == -1 {
-                end = Pos(r, c);
-                *col = 26;
-            }
-        }
-    }
+    fn part2((grid, _start, end): &Self::Parsed<'_>, _params: &()) -> Result<u32> {
+        best_trail(grid, end).map(|(_path, dist)| dist)
 
-    Ok((input, (grid, start, end)))
-}
+        // possible optimizations
+        // - work backwards from the end to the nearest 'a'
+        // 		- modify heuristic to calculate distance to nearest 'a' instead of single point
+        // 		- modify success condition to match on any 'a'
+    }
 
-fn grid_with_multiple_starts(input: &str) -> IResult<&str, (Grid, Vec<Pos>, Pos)> {
-    let (_, mut grid) = separated_list1(
-        newline,
-        alpha1.map(|chars: &str| {
-            chars
-                .chars()
-                .map(|c| match c {
-                    'S' => 1,
-                    'E' => -1,
-                    _ => c as i32 - 96,
+    fn visualize(
+        (grid, start, end): &Self::Parsed<'_>,
+        _params: &(),
+        part: Part,
+        viz: &mut Viz,
+    ) -> Result<String> {
+        let (path, dist) = match part {
+            Part::One => climb(grid, start, end)
+                .ok_or_else(|| AocError::unsolvable("there is no path from `S` to `E`"))?,
+            Part::Two => best_trail(grid, end)?,
+        };
+        let glyphs = grid.map(|&square| (b'a' + square as u8 - 1) as char);
+        let walked = |step: usize| {
+            path[..step]
+                .iter()
+                .fold(Frame::new(&glyphs), |frame, &pos| {
+                    frame.highlight(pos, Colour::Yellow)
                 })
-                .collect::<Vec<i32>>()
-        }),
-    )(input)?;
-
-    let mut starts: Vec<Pos> = vec![];
-    let mut end = Pos::new();
-    for (r, row) in grid.iter_mut().enumerate() {
-        for (c, col) in row.iter_mut().enumerate() {
-            if *col == 1 {
-                starts.push(Pos(r, c));
-            } else if *col == -1 {
-                end = Pos(r, c);
-                *col = 26;
-            }
+                .highlight(*end, Colour::Green)
+                .highlight(path[step - 1], Colour::Red)
+                .caption(format!("step {} of {dist}", step - 1))
+        };
+        for step in 1..=path.len() {
+            viz.frame(|| walked(step));
         }
+        viz.last_frame(|| walked(path.len()));
+        Ok(dist.to_string())
     }
-
-    Ok((input, (grid, starts, end)))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, (grid, start, end)) = grid_start_end(input).unwrap();
+fn climb(grid: &Grid<i32>, start: &Pos, end: &Pos) -> Option<(Vec<Pos>, u32)> {
     astar(
-        &start,
-        |p| p.successors(&grid),
-        |p| p.distance(&end),
-        |p| *p == end,
+        start,
+        |p| successors(grid, p),
+        |p| distance(p, end),
+        |p| p == end,
     )
-    .unwrap()
-    .1
-    .to_string()
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, (grid, starts, end)) = grid_with_multiple_starts(input).unwrap();
+/// The shortest climb from any square at elevation `a`.
+fn best_trail(grid: &Grid<i32>, end: &Pos) -> Result<(Vec<Pos>, u32)> {
+    let starts: Vec<Pos> = grid
+        .iter()
+        .filter(|(_, &square)| square == height('a'))
+        .map(|(pos, _)| pos)
+        .collect();
 
     starts
         .iter()
-        .map(|start| {
-            astar(
-                start,
-                |p| p.successors(&grid),
-                |p| p.distance(&end),
-                |p| *p == end,
-            )
-        })
-        .filter_map(|res| res.map(|(_path, dist)| dist))
-        .min()
-        .unwrap()
-        .to_string()
-
-    // possible optimizations
-    // - work backwards from the end to the nearest 'a'
-    // 		- modify heuristic to calculate distance to nearest 'a' instead of single point
-    // 		- modify success condition to match on any 'a'
+        .filter_map(|start| climb(grid, start, end))
+        .min_by_key(|(_path, dist)| *dist)
+        .ok_or_else(|| AocError::unsolvable("no square at elevation `a` reaches `E`"))
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day12::part1(&Day12::parse(&input)?, &()).map(|answer| answer.to_string())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day12::part2(&Day12::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi";
-
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "31");
-    }
+    aoc_common::example_tests!(Day12);
 
     #[test]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT), "29");
+    fn rejects_squares_other_than_elevations() {
+        let err = Day12::parse("SabB\nzzzE").unwrap_err();
+        assert_eq!(err.to_string(), "unexpected `B` at line 1, column 4: `B`");
     }
 }
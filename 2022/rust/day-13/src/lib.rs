@@ -1,8 +1,14 @@
+use aoc_common::{
+    normalize,
+    parse::{groups, parse_all},
+    AocError, Result, Solution,
+};
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{self, newline},
-    multi::{separated_list0, separated_list1},
+    character::complete::{self, line_ending},
+    error::{Error, ErrorKind},
+    multi::separated_list0,
     sequence::{delimited, separated_pair},
     IResult, Parser,
 };
@@ -43,105 +49,127 @@ impl PartialEq for Packet {
 }
 
 #[derive(Debug)]
-struct Pair {
+pub struct Pair {
     left: Packet,
     right: Packet,
 }
 
+/// Real packets nest a handful of lists deep; this keeps the recursive
+/// parser, comparison and drop of a hostile one from overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
 fn packet(input: &str) -> IResult<&str, Packet> {
+    nested_packet(input, 0)
+}
+
+fn nested_packet(input: &str, depth: usize) -> IResult<&str, Packet> {
+    if depth > MAX_DEPTH {
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge)));
+    }
     alt((
-        delimited(tag("["), separated_list0(tag(","), packet), tag("]")).map(Packet::List),
+        delimited(
+            tag("["),
+            separated_list0(tag(","), |input| nested_packet(input, depth + 1)),
+            tag("]"),
+        )
+        .map(Packet::List),
         complete::u32.map(Packet::Number),
     ))(input)
 }
 
 fn pairs(input: &str) -> IResult<&str, Vec<Pair>> {
-    separated_list1(
-        tag("\n\n"),
-        separated_pair(packet, newline, packet).map(|(p1, p2)| Pair {
+    groups(
+        separated_pair(packet, line_ending, packet).map(|(p1, p2)| Pair {
             left: p1,
             right: p2,
         }),
     )(input)
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, pair_list) = pairs(input).unwrap();
-    pair_list
-        .iter()
-        .enumerate()
-        .filter_map(|(index, Pair { left, right })| match left.cmp(right) {
-            Ordering::Less => Some(index),
-            Ordering::Equal => panic!("equal???"),
-            Ordering::Greater => None,
-        })
-        .map(|i| i + 1)
-        .sum::<usize>()
-        .to_string()
-}
-
-pub fn process_part2(input: &str) -> String {
-    let (_, pair_list) = pairs(input).unwrap();
-    let packet_2 = Packet::List(vec![Packet::List(vec![Packet::Number(2)])]);
-    let packet_6 = Packet::List(vec![Packet::List(vec![Packet::Number(6)])]);
-    let mut packets: Vec<&Packet> = pair_list
-        .iter()
-        .flat_map(|Pair { left, right }| [left, right])
-        .chain([&packet_2, &packet_6])
-        .collect();
-
-    packets.sort();
-    let index_2 = packets
-        .iter()
-        .enumerate()
-        .find(|(_index, packet)| ***packet == packet_2)
-        .map(|(index, _)| index + 1)
-        .unwrap();
-    let index_6 = packets
-        .iter()
-        .enumerate()
-        .find(|(_index, packet)| ***packet == packet_6)
-        .map(|(index, _)| index + 1)
-        .unwrap();
-
-    (index_2 * index_6).to_string()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day13;
 
-    const INPUT: &str = "[1,1,3,1,1]
-[1,1,5,1,1]
+impl Solution for Day13 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 13;
+    const EXAMPLE: &'static str = include_str!("../examples/13-a.txt");
 
-[[1],[2,3,4]]
-[[1],4]
+    type Parsed<'a> = Vec<Pair>;
+    type Params = ();
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-[9]
-[[8,7,6]]
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        parse_all(input, pairs)
+    }
 
-[[4,4],4,4]
-[[4,4],4,4,4]
+    fn part1(pair_list: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        pair_list
+            .iter()
+            .enumerate()
+            .filter_map(|(index, Pair { left, right })| match left.cmp(right) {
+                Ordering::Less => Some(Ok(index + 1)),
+                Ordering::Equal => Some(Err(AocError::unsolvable(format!(
+                    "pair {} is neither in nor out of order",
+                    index + 1
+                )))),
+                Ordering::Greater => None,
+            })
+            .sum::<Result<usize>>()
+    }
 
-[7,7,7,7]
-[7,7,7]
+    fn part2(pair_list: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        let packet_2 = Packet::List(vec![Packet::List(vec![Packet::Number(2)])]);
+        let packet_6 = Packet::List(vec![Packet::List(vec![Packet::Number(6)])]);
+        let mut packets: Vec<&Packet> = pair_list
+            .iter()
+            .flat_map(|Pair { left, right }| [left, right])
+            .chain([&packet_2, &packet_6])
+            .collect();
+
+        packets.sort();
+        let index_2 = packets
+            .iter()
+            .enumerate()
+            .find(|(_index, packet)| ***packet == packet_2)
+            .map(|(index, _)| index + 1)
+            .expect("divider packets are always in the list");
+        let index_6 = packets
+            .iter()
+            .enumerate()
+            .find(|(_index, packet)| ***packet == packet_6)
+            .map(|(index, _)| index + 1)
+            .expect("divider packets are always in the list");
+
+        Ok(index_2 * index_6)
+    }
+}
 
-[]
-[3]
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day13::part1(&Day13::parse(&input)?, &()).map(|answer| answer.to_string())
+}
 
-[[[]]]
-[[]]
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day13::part2(&Day13::parse(&input)?, &()).map(|answer| answer.to_string())
+}
 
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]";
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "13");
-    }
+    aoc_common::example_tests!(Day13);
 
     #[test]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT), "140");
+    fn rejects_deeply_nested_packets() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let input = format!("{}\n[]", nested(MAX_DEPTH));
+        assert!(Day13::parse(&input).is_ok());
+
+        let input = format!("{}\n[]", nested(100_000));
+        let err = Day13::parse(&input).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("too large at line 1, column 66"));
     }
 }
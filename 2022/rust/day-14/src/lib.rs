@@ -1,17 +1,36 @@
+use aoc_common::{
+    grid::Pos,
+    normalize,
+    parse::{lines, parse_all},
+    viz::Colour,
+    AocError, Frame, Grid, Part, Result, Solution, Viz,
+};
 use itertools::Itertools;
 use nom::{
-    bytes::complete::tag,
-    character::complete::{self, line_ending},
-    multi::separated_list1,
-    sequence::separated_pair,
-    IResult,
+    bytes::complete::tag, character::complete, combinator::verify, multi::separated_list1,
+    sequence::separated_pair, IResult,
 };
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, fmt};
+
+/// Far beyond any real scan, but keeps the cave small enough to fill.
+const MAX_COORDINATE: u32 = 1_000;
+
+fn coordinate(input: &str) -> IResult<&str, u32> {
+    verify(complete::u32, |n| *n <= MAX_COORDINATE)(input)
+}
 
 fn line(input: &str) -> IResult<&str, impl Iterator<Item = (u32, u32)>> {
-    let (input, pairs) = separated_list1(
-        tag(" -> "),
-        separated_pair(complete::u32, complete::char(','), complete::u32),
+    let (input, pairs) = verify(
+        separated_list1(
+            tag(" -> "),
+            separated_pair(coordinate, complete::char(','), coordinate),
+        ),
+        |pairs: &Vec<(u32, u32)>| {
+            // every segment of a rock path is horizontal or vertical
+            pairs
+                .windows(2)
+                .all(|ab| ab[0].0 == ab[1].0 || ab[0].1 == ab[1].1)
+        },
     )(input)?;
 
     let result = pairs
@@ -33,123 +52,206 @@ fn line(input: &str) -> IResult<&str, impl Iterator<Item = (u32, u32)>> {
 }
 
 fn rocks(input: &str) -> IResult<&str, BTreeSet<(u32, u32)>> {
-    let (input, pairs) = separated_list1(line_ending, line)(input)?;
+    let (input, pairs) = lines(line)(input)?;
     let map: BTreeSet<(u32, u32)> = pairs.into_iter().flatten().collect();
 
     Ok((input, map))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_input, mut rocks) = rocks(input).unwrap();
-
-    let mut rocks_vec: Vec<&(u32, u32)> = rocks.iter().collect();
-    rocks_vec.sort_by(|a, b| a.1.cmp(&b.1));
-    let lowest_rock = **rocks_vec.last().unwrap();
-    drop(rocks_vec);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Air,
+    Rock,
+    Sand,
+}
 
-    let rock_count = rocks.len();
-    let mut current_sand = (500, 0);
-    loop {
-        if current_sand.1 > lowest_rock.1 {
-            break;
+impl Tile {
+    fn glyph(self) -> char {
+        match self {
+            Tile::Air => '.',
+            Tile::Rock => '#',
+            Tile::Sand => 'o',
         }
-        let down = (current_sand.0, current_sand.1 + 1);
-        let down_left = (current_sand.0 - 1, current_sand.1 + 1);
-        let down_right = (current_sand.0 + 1, current_sand.1 + 1);
-        match (
-            rocks.get(&down),
-            rocks.get(&down_left),
-            rocks.get(&down_right),
-        ) {
-            (None, _, _) => {
-                current_sand = down;
-            }
-            (_, None, _) => {
-                current_sand = down_left;
-            }
-            (_, _, None) => {
-                current_sand = down_right;
-            }
-            (Some(_), Some(_), Some(_)) => {
-                rocks.insert(current_sand);
-                current_sand = (500, 0);
-            }
-        };
     }
+}
 
-    (rocks.len() - rock_count).to_string()
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.glyph())
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_input, mut rocks) = rocks(input).unwrap();
-
-    let mut rocks_vec: Vec<&(u32, u32)> = rocks.iter().collect();
-    rocks_vec.sort_by(|a, b| a.1.cmp(&b.1));
-    let lowest_rock = **rocks_vec.last().unwrap();
-    drop(rocks_vec);
-
-    let rock_count = rocks.len();
-    let mut current_sand = (500, 0);
-    while rocks.get(&(500, 0)).is_none() {
-        let down = (current_sand.0, current_sand.1 + 1);
-        let down_left = (current_sand.0 - 1, current_sand.1 + 1);
-        let down_right = (current_sand.0 + 1, current_sand.1 + 1);
-        match (
-            rocks.get(&down).or({
-                if down.1 == lowest_rock.1 + 2 {
-                    Some(&lowest_rock)
-                } else {
-                    None
-                }
-            }),
-            rocks.get(&down_left).or({
-                if down_left.1 == lowest_rock.1 + 2 {
-                    Some(&lowest_rock)
-                } else {
-                    None
-                }
-            }),
-            rocks.get(&down_right).or({
-                if down_right.1 == lowest_rock.1 + 2 {
-                    Some(&lowest_rock)
-                } else {
-                    None
+/// The slice of the cave that sand can reach, down to just above the floor.
+#[derive(Debug, Clone)]
+pub struct Cave {
+    tiles: Grid<Tile>,
+    source: Pos,
+}
+
+impl Cave {
+    fn new(rocks: &BTreeSet<(u32, u32)>) -> Result<Self> {
+        let lowest_rock = rocks
+            .iter()
+            .map(|rock| rock.1)
+            .max()
+            .ok_or_else(|| AocError::unsolvable("there are no rocks"))?;
+        // sand can't spread further sideways than it falls
+        let spread = lowest_rock + 1;
+        let left = rocks
+            .iter()
+            .map(|rock| rock.0)
+            .min()
+            .unwrap_or(500)
+            .min(500u32.saturating_sub(spread));
+        let right = rocks
+            .iter()
+            .map(|rock| rock.0)
+            .max()
+            .unwrap_or(500)
+            .max(500 + spread);
+
+        let mut tiles = Grid::new(
+            (right - left + 1) as usize,
+            (lowest_rock + 2) as usize,
+            Tile::Air,
+        );
+        for &(x, y) in rocks {
+            tiles[((x - left) as usize, y as usize)] = Tile::Rock;
+        }
+        Ok(Self {
+            tiles,
+            source: ((500 - left) as usize, 0),
+        })
+    }
+
+    /// Drops one unit of sand from the source, returning where it came to
+    /// rest. Without a floor, sand that falls below the lowest rock is lost.
+    fn drop_sand(&mut self, floor: bool) -> Result<Option<Pos>> {
+        if self.tiles[self.source] != Tile::Air {
+            return Ok(None);
+        }
+
+        let (mut x, mut y) = self.source;
+        loop {
+            if y + 1 == self.tiles.height() {
+                if !floor {
+                    return Ok(None);
                 }
-            }),
-        ) {
-            (None, _, _) => {
-                current_sand = down;
+                break;
             }
-            (_, None, _) => {
-                current_sand = down_left;
+            let left = x
+                .checked_sub(1)
+                .ok_or_else(|| AocError::unsolvable("sand flows past x = 0"))?;
+            let next = [(x, y + 1), (left, y + 1), (x + 1, y + 1)]
+                .into_iter()
+                .find(|&pos| self.tiles[pos] == Tile::Air);
+            match next {
+                Some(pos) => (x, y) = pos,
+                None => break,
             }
-            (_, _, None) => {
-                current_sand = down_right;
-            }
-            (Some(_), Some(_), Some(_)) => {
-                rocks.insert(current_sand);
-                current_sand = (500, 0);
-            }
-        };
+        }
+
+        self.tiles[(x, y)] = Tile::Sand;
+        Ok(Some((x, y)))
+    }
+
+    fn fill(&mut self, floor: bool, viz: &mut Viz) -> Result<usize> {
+        let mut settled = 0;
+        let mut last = self.source;
+        while let Some(pos) = self.drop_sand(floor)? {
+            settled += 1;
+            last = pos;
+            viz.frame(|| self.frame(last, settled));
+        }
+        viz.last_frame(|| self.frame(last, settled));
+        Ok(settled)
+    }
+
+    /// The cave with the grain that settled last highlighted.
+    fn frame(&self, last: Pos, settled: usize) -> Frame {
+        Frame::new(&self.tiles.map(|tile| tile.glyph()))
+            .highlight(self.source, Colour::Blue)
+            .highlight(last, Colour::Yellow)
+            .caption(format!("sand at rest: {settled}"))
+    }
+}
+
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tiles.fmt(f)
+    }
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 14;
+    const EXAMPLE: &'static str = include_str!("../examples/14-a.txt");
+
+    type Parsed<'a> = Cave;
+    type Params = ();
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        Cave::new(&parse_all(input, rocks)?)
+    }
+
+    fn part1(cave: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        cave.clone().fill(false, &mut Viz::off())
+    }
+
+    fn part2(cave: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+        cave.clone().fill(true, &mut Viz::off())
+    }
+
+    fn visualize(
+        cave: &Self::Parsed<'_>,
+        _params: &(),
+        part: Part,
+        viz: &mut Viz,
+    ) -> Result<String> {
+        let floor = part == Part::Two;
+        cave.clone()
+            .fill(floor, viz)
+            .map(|answer| answer.to_string())
     }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day14::part1(&Day14::parse(&input)?, &()).map(|answer| answer.to_string())
+}
 
-    (rocks.len() - rock_count).to_string()
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day14::part2(&Day14::parse(&input)?, &()).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::viz::TextLog;
 
-    const INPUT: &str = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
+    aoc_common::example_tests!(Day14);
 
     #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "24");
+    fn visualizes_each_unit_of_sand() {
+        let cave = Day14::parse(Day14::EXAMPLE).unwrap();
+        let mut log = TextLog::new(vec![]);
+        let mut viz = Viz::new(&mut log).every(5);
+        assert_eq!(
+            Day14::visualize(&cave, &(), Part::One, &mut viz).unwrap(),
+            "24"
+        );
+        assert_eq!(viz.finish().unwrap(), 6);
     }
 
     #[test]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT), "93");
+    fn rejects_diagonal_and_distant_rock() {
+        assert!(Day14::parse("498,4 -> 500,6").is_err());
+        assert!(Day14::parse("498,4 -> 4000000000,4").is_err());
+        assert!(Day14::parse("498,4 -> 498,6 -> 496,6").is_ok());
     }
 }
@@ -1,225 +1,295 @@
-use geo::{Coord, Line};
-use intersect2d::{intersect, Intersection};
+use aoc_common::{
+    normalize,
+    params::{FromParams, ParamError, RawParams},
+    parse::{labelled_point2, lines, parse_all},
+    tracing::debug,
+    AocError, Interval, IntervalSet, Point2, Result, Solution,
+};
+use geo::{
+    line_intersection::{line_intersection, LineIntersection},
+    Coord, Line,
+};
 use nom::{
     bytes::complete::tag,
-    character::complete::{self, line_ending},
-    multi::separated_list1,
-    sequence::{preceded, separated_pair, tuple},
+    sequence::{preceded, tuple},
     IResult,
 };
 use std::collections::{BTreeMap, BTreeSet};
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct Pos {
-    x: i32,
-    y: i32,
+pub const TARGET_ROW: i32 = 2_000_000;
+pub const SEARCH_SPACE: f64 = 4_000_000.;
+
+/// The example in the puzzle text uses a target row of 10 and a search space
+/// of 20 instead of the values used for the real input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Day15Params {
+    pub target_row: i32,
+    pub search_space: f64,
 }
 
-impl Pos {
-    fn zero() -> Self {
-        Self { x: 0, y: 0 }
+impl Default for Day15Params {
+    fn default() -> Self {
+        Self {
+            target_row: TARGET_ROW,
+            search_space: SEARCH_SPACE,
+        }
     }
+}
 
-    fn distance(&self, other: &Self) -> i32 {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
+impl FromParams for Day15Params {
+    fn from_params(params: &RawParams) -> Result<Self, ParamError> {
+        Ok(Self {
+            target_row: params.get_or("target_row", TARGET_ROW)?,
+            search_space: params.get_or("search_space", SEARCH_SPACE)?,
+        })
     }
 }
 
-type Sensor = Pos;
-type Beacon = Pos;
+pub type Pos = Point2<i32>;
 
-fn position(input: &str) -> IResult<&str, Pos> {
-    let (input, (x, y)) = separated_pair(
-        preceded(tag("x="), complete::i32),
-        tag(", "),
-        preceded(tag("y="), complete::i32),
-    )(input)?;
+/// How far from the origin a sensor or beacon may be. Anything within this
+/// keeps every distance and border line the solvers work out inside an `i32`.
+pub const COORDINATE_LIMIT: i32 = 1 << 27;
 
-    Ok((input, Pos { x, y }))
-}
+type Sensor = Pos;
+type Beacon = Pos;
 
 fn line(input: &str) -> IResult<&str, (Sensor, Beacon)> {
     let (input, pair) = tuple((
-        preceded(tag("Sensor at "), position),
-        preceded(tag(": closest beacon is at "), position),
+        preceded(tag("Sensor at "), labelled_point2),
+        preceded(tag(": closest beacon is at "), labelled_point2),
     ))(input)?;
 
     Ok((input, pair))
 }
 
-fn sensor_beacon_map(input: &str) -> IResult<&str, BTreeMap<Sensor, Beacon>> {
-    let (input, list) = separated_list1(line_ending, line)(input)?;
+fn generate_border_lines(sensor: &Sensor, range: i32) -> BTreeSet<(Pos, Pos)> {
+    let reach = range + 1;
+    let east = *sensor + Pos::new(reach, 0);
+    let south = *sensor - Pos::new(0, reach);
+    let west = *sensor - Pos::new(reach, 0);
+    let north = *sensor + Pos::new(0, reach);
 
-    Ok((
-        input,
-        list.into_iter().collect::<BTreeMap<Sensor, Beacon>>(),
-    ))
+    BTreeSet::from([(east, south), (south, west), (west, north), (north, east)])
 }
 
-fn determine_boundaries(pairs: &BTreeMap<Sensor, Beacon>, target_row: i32) -> (i32, i32) {
-    let mut left_bound = i32::MAX;
-    let mut right_bound = i32::MIN;
-
-    for (sensor, beacon) in pairs.iter() {
-        let distance_to_beacon = sensor.distance(beacon);
-        let distance_to_target_row = sensor.distance(&Pos {
-            x: sensor.x,
-            y: target_row,
-        });
-        let x_range = distance_to_beacon - distance_to_target_row;
-        let left_x = sensor.x - x_range;
-        let right_x = sensor.x + x_range;
-
-        left_bound = left_bound.min(left_x);
-        right_bound = right_bound.max(right_x);
-    }
-
-    (left_bound, right_bound)
-}
+pub struct Day15;
 
-pub fn process_part1(input: &str, target_row: i32) -> String {
-    let (_, sensor_beacon_map) = sensor_beacon_map(input).unwrap();
-    let (left_bound, right_bound) = determine_boundaries(&sensor_beacon_map, target_row);
-    let mut covered = 0;
+impl Solution for Day15 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 15;
+    const EXAMPLE: &'static str = include_str!("../examples/15-a.txt");
 
-    for x in left_bound..=right_bound {
-        let curr_point = Pos { x, y: target_row };
+    type Parsed<'a> = BTreeMap<Sensor, Beacon>;
+    type Params = Day15Params;
+    type Answer1 = i32;
+    type Answer2 = i64;
 
-        if sensor_beacon_map
-            .values()
-            .collect::<BTreeSet<&Pos>>()
-            .contains(&curr_point)
-        {
-            continue;
-        }
-
-        for (sensor, beacon) in &sensor_beacon_map {
-            if sensor.distance(&curr_point) <= sensor.distance(beacon) {
-                covered += 1;
-                break;
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        let pairs = parse_all(input, lines(line))?;
+        let limits = -COORDINATE_LIMIT..=COORDINATE_LIMIT;
+        for (text, (sensor, beacon)) in input.lines().zip(&pairs) {
+            if [sensor, beacon]
+                .iter()
+                .any(|pos| !limits.contains(&pos.x) || !limits.contains(&pos.y))
+            {
+                return Err(AocError::parse(
+                    input,
+                    text,
+                    format!("expected coordinates within {COORDINATE_LIMIT} of 0"),
+                ));
             }
         }
+        Ok(pairs.into_iter().collect())
     }
 
-    covered.to_string()
-}
-
-fn generate_border_lines(sensor: &Sensor, range: i32) -> BTreeSet<(Pos, Pos)> {
-    let east = Pos {
-        x: sensor.x + range + 1,
-        y: sensor.y,
-    };
-    let south = Pos {
-        x: sensor.x,
-        y: sensor.y - range - 1,
-    };
-    let west = Pos {
-        x: sensor.x - range - 1,
-        y: sensor.y,
-    };
-    let north = Pos {
-        x: sensor.x,
-        y: sensor.y + range + 1,
-    };
-
-    BTreeSet::from([
-        (east.clone(), south.clone()),
-        (south.clone(), west.clone()),
-        (west.clone(), north.clone()),
-        (north.clone(), east.clone()),
-    ])
-}
+    fn part1(sensor_beacon_map: &Self::Parsed<'_>, params: &Self::Params) -> Result<i32> {
+        let row = params.target_row;
+        let covered: IntervalSet<i32> = sensor_beacon_map
+            .iter()
+            .filter_map(|(sensor, beacon)| {
+                // the row can be anywhere, so is only compared in an `i64`
+                let reach = i64::from(sensor.manhattan(beacon)) - i64::from(sensor.y.abs_diff(row));
+                let reach = i32::try_from(reach).ok()?;
+                Interval::new(sensor.x - reach, sensor.x + reach)
+            })
+            .collect();
 
-pub fn process_part2(input: &str, search_space: f64) -> String {
-    let (_, sensor_beacon_map) = sensor_beacon_map(input).unwrap();
-    let mut output: Pos = Pos::zero();
-    let mut lines: BTreeSet<(Pos, Pos)> = BTreeSet::new();
-    let mut intersections: BTreeSet<Pos> = BTreeSet::new();
-    for (sensor, beacon) in &sensor_beacon_map {
-        let border_lines = generate_border_lines(sensor, sensor.distance(beacon));
-        lines = lines.union(&border_lines).map(|ls| ls.to_owned()).collect();
+        // Every beacon on the row is inside its own sensor's span.
+        let beacons = sensor_beacon_map
+            .values()
+            .filter(|beacon| beacon.y == row)
+            .collect::<BTreeSet<_>>()
+            .len() as i32;
+        Ok(covered.coverage() - beacons)
     }
 
-    let lines_vec1 = lines
-        .iter()
-        .map(|(first, second)| {
-            let mut coord1 = Coord::zero();
-            let mut coord2 = Coord::zero();
-            coord1.x = first.x as f64;
-            coord1.y = first.y as f64;
-            coord2.x = second.x as f64;
-            coord2.y = second.y as f64;
-            Line::new(coord1, coord2)
-        })
-        .collect::<Vec<Line>>();
-    let lines_vec2 = lines_vec1.clone();
+    fn part2(sensor_beacon_map: &Self::Parsed<'_>, params: &Self::Params) -> Result<i64> {
+        let mut output: Option<Pos> = None;
+        let mut lines: BTreeSet<(Pos, Pos)> = BTreeSet::new();
+        let mut intersections: BTreeSet<Pos> = BTreeSet::new();
+        for (sensor, beacon) in sensor_beacon_map {
+            let border_lines = generate_border_lines(sensor, sensor.manhattan(beacon));
+            lines = lines.union(&border_lines).map(|ls| ls.to_owned()).collect();
+        }
 
-    for line1 in &lines_vec1 {
-        for line2 in &lines_vec2 {
-            if line1 == line2 {
-                continue;
-            }
-            let intersection_point = intersect(line1, line2);
-            if let Some(Intersection::Intersection(c)) = intersection_point {
-                let within_bounds: bool =
-                    0. <= c.x && c.x <= search_space && 0. <= c.y && c.y <= search_space;
-                if within_bounds {
-                    intersections.insert(Pos {
-                        x: c.x as i32,
-                        y: c.y as i32,
-                    });
+        let lines_vec1 = lines
+            .iter()
+            .map(|(first, second)| {
+                let mut coord1 = Coord::zero();
+                let mut coord2 = Coord::zero();
+                coord1.x = first.x as f64;
+                coord1.y = first.y as f64;
+                coord2.x = second.x as f64;
+                coord2.y = second.y as f64;
+                Line::new(coord1, coord2)
+            })
+            .collect::<Vec<Line>>();
+        let lines_vec2 = lines_vec1.clone();
+
+        for line1 in &lines_vec1 {
+            for line2 in &lines_vec2 {
+                if line1 == line2 {
+                    continue;
+                }
+                let intersection_point = line_intersection(*line1, *line2);
+                if let Some(LineIntersection::SinglePoint {
+                    intersection: c, ..
+                }) = intersection_point
+                {
+                    let within_bounds: bool = 0. <= c.x
+                        && c.x <= params.search_space
+                        && 0. <= c.y
+                        && c.y <= params.search_space;
+                    if within_bounds {
+                        intersections.insert(Pos::new(c.x as i32, c.y as i32));
+                    }
+                } else {
+                    continue;
                 }
-            } else {
-                continue;
-            }
 
-            for point in &intersections {
-                let mut covered = false;
-                for (sensor, beacon) in &sensor_beacon_map {
-                    if point.distance(sensor) <= sensor.distance(beacon) {
-                        covered = true;
+                for point in &intersections {
+                    let mut covered = false;
+                    for (sensor, beacon) in sensor_beacon_map {
+                        if point.manhattan(sensor) <= sensor.manhattan(beacon) {
+                            covered = true;
+                            break;
+                        }
+                    }
+                    if !covered {
+                        output = Some(*point);
                         break;
                     }
                 }
-                if !covered {
-                    output = point.clone();
-                    break;
-                }
             }
         }
+
+        debug!(beacon = ?output, "checked intersections");
+
+        let output = output.ok_or_else(|| {
+            AocError::unsolvable("every position in the search space is covered by a sensor")
+        })?;
+        Ok(output.x as i64 * 4_000_000 + output.y as i64)
     }
+}
 
-    dbg!(&output);
+pub fn process_part1(input: &str, target_row: i32) -> Result<String> {
+    let input = normalize(input);
+    let params = Day15Params {
+        target_row,
+        ..Day15Params::default()
+    };
+    Day15::part1(&Day15::parse(&input)?, &params).map(|answer| answer.to_string())
+}
 
-    (output.x as i64 * 4_000_000 + output.y as i64).to_string()
+pub fn process_part2(input: &str, search_space: f64) -> Result<String> {
+    let input = normalize(input);
+    let params = Day15Params {
+        search_space,
+        ..Day15Params::default()
+    };
+    Day15::part2(&Day15::parse(&input)?, &params).map(|answer| answer.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::testing::sensor_beacons;
 
-    const INPUT: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-Sensor at x=9, y=16: closest beacon is at x=10, y=16
-Sensor at x=13, y=2: closest beacon is at x=15, y=3
-Sensor at x=12, y=14: closest beacon is at x=10, y=16
-Sensor at x=10, y=20: closest beacon is at x=10, y=16
-Sensor at x=14, y=17: closest beacon is at x=10, y=16
-Sensor at x=8, y=7: closest beacon is at x=2, y=10
-Sensor at x=2, y=0: closest beacon is at x=2, y=10
-Sensor at x=0, y=11: closest beacon is at x=2, y=10
-Sensor at x=20, y=14: closest beacon is at x=25, y=17
-Sensor at x=17, y=20: closest beacon is at x=21, y=22
-Sensor at x=16, y=7: closest beacon is at x=15, y=3
-Sensor at x=14, y=3: closest beacon is at x=15, y=3
-Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+    aoc_common::example_tests!(Day15);
 
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT, 10), "26");
+    fn determine_boundaries(pairs: &BTreeMap<Sensor, Beacon>, target_row: i32) -> (i32, i32) {
+        let mut left_bound = i32::MAX;
+        let mut right_bound = i32::MIN;
+
+        for (sensor, beacon) in pairs.iter() {
+            let distance_to_beacon = sensor.manhattan(beacon);
+            let distance_to_target_row = sensor.manhattan(&Pos::new(sensor.x, target_row));
+            let x_range = distance_to_beacon - distance_to_target_row;
+            let left_x = sensor.x - x_range;
+            let right_x = sensor.x + x_range;
+
+            left_bound = left_bound.min(left_x);
+            right_bound = right_bound.max(right_x);
+        }
+
+        (left_bound, right_bound)
+    }
+
+    /// Checks every x between the outermost sensors' reach.
+    fn scan_part1(
+        sensor_beacon_map: &BTreeMap<Sensor, Beacon>,
+        params: &Day15Params,
+    ) -> Result<i32> {
+        let (left_bound, right_bound) = determine_boundaries(sensor_beacon_map, params.target_row);
+        let mut covered = 0;
+
+        for x in left_bound..=right_bound {
+            let curr_point = Pos::new(x, params.target_row);
+
+            if sensor_beacon_map
+                .values()
+                .collect::<BTreeSet<&Pos>>()
+                .contains(&curr_point)
+            {
+                continue;
+            }
+
+            for (sensor, beacon) in sensor_beacon_map {
+                if sensor.manhattan(&curr_point) <= sensor.manhattan(beacon) {
+                    covered += 1;
+                    break;
+                }
+            }
+        }
+
+        Ok(covered)
     }
 
     #[test]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT, 20.), "56000011");
+    fn rejects_coordinates_too_far_out() {
+        let input = "Sensor at x=0, y=0: closest beacon is at x=1, y=0\n\
+                     Sensor at x=-2147483648, y=0: closest beacon is at x=2147483647, y=0";
+        assert_eq!(
+            Day15::parse(input).err().unwrap().to_string(),
+            "expected coordinates within 134217728 of 0 at line 2, column 1: \
+             `Sensor at x=-2147483648, y=0: closest be`"
+        );
+
+        let far = COORDINATE_LIMIT;
+        let input = format!("Sensor at x={far}, y=-{far}: closest beacon is at x=-{far}, y={far}");
+        let parsed = Day15::parse(&input).unwrap();
+        let params = |target_row| Day15Params {
+            target_row,
+            search_space: f64::from(i32::MAX),
+        };
+        assert_eq!(Day15::part1(&parsed, &params(i32::MIN)).unwrap(), 0);
+        assert!(Day15::part1(&parsed, &params(0)).is_ok());
+        assert!(Day15::part2(&parsed, &params(0)).is_ok());
+    }
+
+    aoc_common::differential_tests! {
+        Day15, params = Day15Params { target_row: 10, search_space: 20. };
+        part1_matches_scan: part1 => scan_part1, sensor_beacons(-10..=30, 1..12);
     }
 }
@@ -1,34 +1,43 @@
-pub fn process_part1(input: &str) -> String {
-    todo!("one")
+use aoc_common::{normalize, Result, Solution};
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 16;
+    const EXAMPLE: &'static str = include_str!("../examples/16-a.txt");
+
+    type Parsed<'a> = &'a str;
+    type Params = ();
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        Ok(input)
+    }
+
+    fn part1(_input: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!("one")
+    }
+
+    fn part2(_input: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!("two")
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day16::part1(&Day16::parse(&input)?, &())
 }
 
-pub fn process_part2(input: &str) -> String {
-    todo!("two")
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day16::part2(&Day16::parse(&input)?, &())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve JJ has flow rate=21; tunnel leads to valve II";
-
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "1651");
-    }
-
-    #[test]
-    #[ignore]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT), "1651");
-    }
+    aoc_common::example_tests!(Day16);
 }
@@ -1,3 +1,4 @@
+use aoc_common::{normalize, parse::parse_all, Result, Solution};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -42,7 +43,7 @@ impl RockFormation {
 }
 
 #[derive(Debug)]
-enum Move {
+pub enum Move {
     Left,
     Right,
 }
@@ -57,7 +58,7 @@ fn rocks(input: &str) -> IResult<&str, Vec<RockFormation>> {
                 complete::char('.').map(|_| Rock::Gap),
             ))),
         )
-        .map(|rocks| RockFormation(rocks)),
+        .map(RockFormation),
     )(input)
 }
 
@@ -68,74 +69,79 @@ fn moves(input: &str) -> IResult<&str, Vec<Move>> {
     )))(input)
 }
 
-pub fn process_part1(input: &str) -> String {
-    let rock_limit = 2022;
-    let mut rocks_stopped: u32 = 0;
-    let (_, moves) = moves(input).unwrap();
-    let (_, rocks) = rocks(ROCKS).unwrap();
-    let mut rocks = rocks.iter().cycle();
-    let mut moves = moves.iter().cycle();
-    let field: BTreeMap<(u32, u32), Rock> = BTreeMap::new();
-    while rocks_stopped != 2022 {
-        let current_rock = rocks.next().unwrap();
-        let max_rock_height = field
-            .keys()
-            .map(|(_, y)| y)
-            .max()
-            .unwrap_or(&0);
-        let current_rock_position: (u32, u32) = (
-            2,
-            max_rock_height + 3 + current_rock.height(),
-        );
-        // while let Some() = field
-        loop {
-            let next_move = moves.next().unwrap();
-            let current_position = match next_move {
-                Move::Left => {
-                    if current_rock_position.0 == 0 {
-                        current_rock_position
-                    } else {
-                        (
-                            current_rock_position.0 - 1,
-                            current_rock_position.1,
-                        )
+pub struct Day17;
+
+impl Solution for Day17 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 17;
+    const EXAMPLE: &'static str = include_str!("../examples/17-a.txt");
+
+    type Parsed<'a> = Vec<Move>;
+    type Params = ();
+    type Answer1 = u32;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        parse_all(input, moves)
+    }
+
+    // TODO: unfinished, the falling rock never comes to rest so this never returns
+    #[allow(unused, clippy::never_loop, clippy::while_immutable_condition)]
+    fn part1(moves: &Self::Parsed<'_>, _params: &()) -> Result<u32> {
+        let rock_limit = 2022;
+        let mut rocks_stopped: u32 = 0;
+        let (_, rocks) = rocks(ROCKS).unwrap();
+        let mut rocks = rocks.iter().cycle();
+        let mut moves = moves.iter().cycle();
+        let field: BTreeMap<(u32, u32), Rock> = BTreeMap::new();
+        while rocks_stopped != 2022 {
+            let current_rock = rocks.next().unwrap();
+            let max_rock_height = field.keys().map(|(_, y)| y).max().unwrap_or(&0);
+            let current_rock_position: (u32, u32) =
+                (2, max_rock_height + 3 + current_rock.height());
+            // while let Some() = field
+            loop {
+                let next_move = moves.next().unwrap();
+                let current_position = match next_move {
+                    Move::Left => {
+                        if current_rock_position.0 == 0 {
+                            current_rock_position
+                        } else {
+                            (current_rock_position.0 - 1, current_rock_position.1)
+                        }
                     }
-                }
-                Move::Right => {
-                    if current_rock_position.0 == 6 {
-                        current_rock_position
-                    } else {
-                        (
-                            current_rock_position.0 + 1,
-                            current_rock_position.1,
-                        )
+                    Move::Right => {
+                        if current_rock_position.0 == 6 {
+                            current_rock_position
+                        } else {
+                            (current_rock_position.0 + 1, current_rock_position.1)
+                        }
                     }
-                }
-            };
+                };
+            }
         }
+
+        Ok(*field.keys().map(|(x, y)| y).max().unwrap())
     }
 
-    field.keys().map(|(x, y)| y).max().unwrap().to_string()
+    fn part2(_moves: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!("two")
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    todo!("two")
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day17::part1(&Day17::parse(&input)?, &()).map(|answer| answer.to_string())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day17::part2(&Day17::parse(&input)?, &())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "3068");
-    }
-
-    #[test]
-    #[ignore]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT), "3068");
-    }
+    aoc_common::example_tests!(Day17);
 }
@@ -1,37 +1,43 @@
-pub fn process_part1(input: &str) -> String {
-    todo!("one")
+use aoc_common::{normalize, Result, Solution};
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 18;
+    const EXAMPLE: &'static str = include_str!("../examples/18-a.txt");
+
+    type Parsed<'a> = &'a str;
+    type Params = ();
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        Ok(input)
+    }
+
+    fn part1(_input: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!("one")
+    }
+
+    fn part2(_input: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!("two")
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day18::part1(&Day18::parse(&input)?, &())
 }
 
-pub fn process_part2(input: &str) -> String {
-    todo!("two")
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day18::part2(&Day18::parse(&input)?, &())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "2,2,2
-1,2,2
-3,2,2
-2,1,2
-2,3,2
-2,2,1
-2,2,3
-2,2,4
-2,2,6
-1,2,5
-3,2,5
-2,1,5
-2,3,5";
-
-    #[test]
-    fn part1_works() {
-        assert_eq!(process_part1(INPUT), "64");
-    }
-
-    #[test]
-    #[ignore]
-    fn part2_works() {
-        assert_eq!(process_part2(INPUT), "64");
-    }
+    aoc_common::example_tests!(Day18);
 }
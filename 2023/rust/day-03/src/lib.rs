@@ -1,28 +1,43 @@
-pub fn process_part1(input: &str) -> String {
-    todo!()
+use aoc_common::{normalize, Result, Solution};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 3;
+    const EXAMPLE: &'static str = "";
+
+    type Parsed<'a> = &'a str;
+    type Params = ();
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        Ok(input)
+    }
+
+    fn part1(_input: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!()
+    }
+
+    fn part2(_input: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!()
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day03::part1(&Day03::parse(&input)?, &())
 }
 
-pub fn process_part2(input: &str) -> String {
-    todo!()
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day03::part2(&Day03::parse(&input)?, &())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "";
-
-    #[test]
-    fn part1_works() {
-        let expected = String::from("");
-        let result = process_part1(INPUT);
-        assert_eq!(expected, result);
-    }
-
-    #[test]
-    fn part2_works() {
-        let expected = String::from("");
-        let result = process_part2(INPUT);
-        assert_eq!(expected, result);
-    }
+    aoc_common::example_tests!(Day03);
 }
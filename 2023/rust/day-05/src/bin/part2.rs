@@ -1,6 +0,0 @@
-use day_05::process_part2;
-
-fn main() {
-    let input = include_str!("../../input.txt");
-    println!("{}", process_part2(input));
-}
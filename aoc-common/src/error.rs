@@ -0,0 +1,144 @@
+use crate::params::ParamError;
+use nom::IResult;
+use std::fmt;
+
+pub type Result<T, E = AocError> = std::result::Result<T, E>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AocError {
+    /// The input did not match the expected format.
+    Parse {
+        line: usize,
+        column: usize,
+        snippet: String,
+        reason: String,
+    },
+    /// The input parsed, but has no answer.
+    Unsolvable(String),
+    InvalidParams(ParamError),
+}
+
+impl AocError {
+    /// A parse error at `at`, which must be a slice of `input`.
+    ///
+    /// Line and column are 1-based and the snippet is the rest of the
+    /// offending line.
+    pub fn parse(input: &str, at: &str, reason: impl Into<String>) -> Self {
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| *offset <= input.len())
+            .unwrap_or(input.len());
+        let offset = (0..=offset)
+            .rev()
+            .find(|offset| input.is_char_boundary(*offset))
+            .unwrap_or(0);
+        let consumed = &input[..offset];
+        let line = consumed.matches('\n').count() + 1;
+        let line_start = consumed.rfind('\n').map_or(0, |newline| newline + 1);
+        let column = consumed[line_start..].chars().count() + 1;
+        let snippet = input[offset..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(40)
+            .collect();
+
+        AocError::Parse {
+            line,
+            column,
+            snippet,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn unsolvable(reason: impl Into<String>) -> Self {
+        AocError::Unsolvable(reason.into())
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::Parse {
+                line,
+                column,
+                snippet,
+                reason,
+            } => write!(f, "{reason} at line {line}, column {column}: `{snippet}`"),
+            AocError::Unsolvable(reason) => write!(f, "no solution: {reason}"),
+            AocError::InvalidParams(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for AocError {}
+
+impl From<ParamError> for AocError {
+    fn from(err: ParamError) -> Self {
+        AocError::InvalidParams(err)
+    }
+}
+
+/// Runs a nom parser over `input`, turning a failure into an
+/// [`AocError::Parse`] pointing at where the parser gave up.
+///
+/// Anything left over after the parser succeeds is ignored.
+pub fn run_parser<'a, O>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> Result<O> {
+    parser(input)
+        .map(|(_, output)| output)
+        .map_err(|err| from_nom(input, err))
+}
+
+pub(crate) fn from_nom(input: &str, err: nom::Err<nom::error::Error<&str>>) -> AocError {
+    match err {
+        nom::Err::Error(err) | nom::Err::Failure(err) => {
+            let reason = match err.code {
+                nom::error::ErrorKind::TooLarge => "too large".to_string(),
+                code => format!("expected {}", code.description()),
+            };
+            AocError::parse(input, err.input, reason)
+        }
+        nom::Err::Incomplete(_) => {
+            AocError::parse(input, &input[input.len()..], "unexpected end of input")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{character::complete, multi::separated_list1};
+
+    #[test]
+    fn parse_errors_point_at_the_failure() {
+        let input = "1,2\n3,x,4";
+        let err = AocError::parse(input, &input[6..], "expected a number");
+        assert_eq!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 3,
+                snippet: "x,4".to_string(),
+                reason: "expected a number".to_string(),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "expected a number at line 2, column 3: `x,4`"
+        );
+    }
+
+    #[test]
+    fn run_parser_reports_nom_errors() {
+        let numbers = |input| separated_list1(complete::char(','), complete::u32)(input);
+        assert_eq!(run_parser("1,2,3", numbers), Ok(vec![1, 2, 3]));
+        let Err(AocError::Parse { line, column, .. }) = run_parser("a,2", numbers) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((line, column), (1, 1));
+    }
+}
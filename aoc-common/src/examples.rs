@@ -0,0 +1,26 @@
+//! Support for the tests generated by [`example_tests!`](crate::example_tests).
+
+use crate::{
+    input::normalize,
+    params::RawParams,
+    solution::{prepare, Part, Solution},
+};
+
+/// Solves `part` of an example input and asserts that the answer is
+/// `expected`, ignoring trailing whitespace.
+pub fn check<S>(input: &str, part: Part, expected: &str, params: &[(&str, &str)])
+where
+    S: Solution + 'static,
+    S::Params: 'static,
+{
+    let params: RawParams = params
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let input = normalize(input);
+    let answer = prepare::<S>(&input, &params).and_then(|prepared| prepared.part(part));
+    match answer {
+        Ok(answer) => assert_eq!(answer.trim_end(), expected.trim_end()),
+        Err(err) => panic!("part {part}: {err}"),
+    }
+}
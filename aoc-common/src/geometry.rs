@@ -0,0 +1,333 @@
+use nom::{
+    bytes::complete::tag,
+    character::complete,
+    combinator::{map, map_res},
+    sequence::{preceded, separated_pair, tuple},
+    IResult,
+};
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// An integer type that can be used as a coordinate.
+pub trait Scalar:
+    Copy + Ord + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs_diff(self, other: Self) -> Self;
+
+    /// A nom parser for a possibly signed decimal number.
+    fn parse(input: &str) -> IResult<&str, Self>;
+}
+
+macro_rules! scalar {
+    ($($t:ty => $parser:expr),* $(,)?) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs_diff(self, other: Self) -> Self {
+                    self.max(other) - self.min(other)
+                }
+
+                fn parse(input: &str) -> IResult<&str, Self> {
+                    $parser(input)
+                }
+            }
+        )*
+    };
+}
+
+scalar! {
+    i32 => complete::i32,
+    i64 => complete::i64,
+    isize => map_res(complete::i64, isize::try_from),
+    u32 => complete::u32,
+    u64 => complete::u64,
+    u128 => complete::u128,
+    usize => map_res(complete::u64, usize::try_from),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+macro_rules! point_ops {
+    ($point:ident { $($axis:ident),* }) => {
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self { $($axis: self.$axis + other.$axis),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self { $($axis: self.$axis - other.$axis),* }
+            }
+        }
+
+        impl<T: Copy + Add<Output = T>> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Copy + Sub<Output = T>> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        /// Scales every axis.
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, scale: T) -> Self {
+                Self { $($axis: self.$axis * scale),* }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($axis: -self.$axis),* }
+            }
+        }
+
+        impl<T: Scalar> $point<T> {
+            pub fn manhattan(&self, other: &Self) -> T {
+                T::ZERO $(+ self.$axis.abs_diff(other.$axis))*
+            }
+
+            pub fn chebyshev(&self, other: &Self) -> T {
+                T::ZERO $(.max(self.$axis.abs_diff(other.$axis)))*
+            }
+        }
+    };
+}
+
+point_ops!(Point2 { x, y });
+point_ops!(Point3 { x, y, z });
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Scalar> Point2<T> {
+    /// Moves one unit in `dir`. Like [`Grid`](crate::Grid), `y` grows
+    /// downwards, so [`Direction::Up`] decreases it.
+    ///
+    /// Unsigned coordinates overflow when stepping off zero.
+    pub fn step(self, dir: Direction) -> Self {
+        match dir {
+            Direction::Up => Self::new(self.x, self.y - T::ONE),
+            Direction::Right => Self::new(self.x + T::ONE, self.y),
+            Direction::Down => Self::new(self.x, self.y + T::ONE),
+            Direction::Left => Self::new(self.x - T::ONE, self.y),
+        }
+    }
+
+    /// The orthogonal neighbours, clockwise from up.
+    pub fn neighbors4(self) -> [Self; 4] {
+        Direction::ALL.map(|dir| self.step(dir))
+    }
+
+    /// The orthogonal and diagonal neighbours, clockwise from up-left.
+    pub fn neighbors8(self) -> [Self; 8] {
+        let up = self.step(Direction::Up);
+        let down = self.step(Direction::Down);
+        [
+            up.step(Direction::Left),
+            up,
+            up.step(Direction::Right),
+            self.step(Direction::Right),
+            down.step(Direction::Right),
+            down,
+            down.step(Direction::Left),
+            self.step(Direction::Left),
+        ]
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Scalar> Point3<T> {
+    /// The neighbours sharing a face, one step either way along each axis.
+    pub fn neighbors6(self) -> [Self; 6] {
+        let Self { x, y, z } = self;
+        let one = T::ONE;
+        [
+            Self::new(x - one, y, z),
+            Self::new(x + one, y, z),
+            Self::new(x, y - one, z),
+            Self::new(x, y + one, z),
+            Self::new(x, y, z - one),
+            Self::new(x, y, z + one),
+        ]
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Reads `U`/`R`/`D`/`L` or `^`/`>`/`v`/`<`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'U' | '^' => Some(Direction::Up),
+            'R' | '>' => Some(Direction::Right),
+            'D' | 'v' => Some(Direction::Down),
+            'L' | '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        let index = self as usize;
+        match turn {
+            Turn::Right => Self::ALL[(index + 1) % 4],
+            Turn::Left => Self::ALL[(index + 3) % 4],
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        self.turn(Turn::Right).turn(Turn::Right)
+    }
+}
+
+/// Parses `x=<x>, y=<y>`.
+pub fn labelled_point2<T: Scalar>(input: &str) -> IResult<&str, Point2<T>> {
+    map(
+        separated_pair(
+            preceded(tag("x="), T::parse),
+            tag(", "),
+            preceded(tag("y="), T::parse),
+        ),
+        Point2::from,
+    )(input)
+}
+
+/// Parses `<x>,<y>,<z>`.
+pub fn csv_point3<T: Scalar>(input: &str) -> IResult<&str, Point3<T>> {
+    map(
+        tuple((
+            T::parse,
+            preceded(tag(","), T::parse),
+            preceded(tag(","), T::parse),
+        )),
+        Point3::from,
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_and_moves_points() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(-3, 4);
+        assert_eq!(a + b, Point2::new(-2, 2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(-a * 2, Point2::new(-2, 4));
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+        assert_eq!(Point2::<usize>::new(5, 1).manhattan(&Point2::new(2, 3)), 5);
+        assert_eq!(Point3::new(1, 2, 3).manhattan(&Point3::new(3, 2, 1)), 4);
+
+        assert_eq!(a.step(Direction::Up), Point2::new(1, -3));
+        assert_eq!(a.neighbors4()[1], Point2::new(2, -2));
+        assert!(a.neighbors8().iter().all(|n| n.chebyshev(&a) == 1));
+        assert!(Point3::new(0, 0, 0)
+            .neighbors6()
+            .iter()
+            .all(|n| n.manhattan(&Point3::default()) == 1));
+
+        assert_eq!(Direction::Up.turn(Turn::Left), Direction::Left);
+        assert_eq!(Direction::Left.turn(Turn::Right), Direction::Up);
+        assert_eq!(Direction::Right.opposite(), Direction::Left);
+    }
+
+    #[test]
+    fn parses_points() {
+        assert_eq!(
+            labelled_point2::<i32>("x=-2, y=15: rest"),
+            Ok((": rest", Point2::new(-2, 15)))
+        );
+        assert_eq!(
+            csv_point3::<u32>("2,2,2\n"),
+            Ok(("\n", Point3::new(2, 2, 2)))
+        );
+        assert!(csv_point3::<u32>("2,2").is_err());
+
+        assert_eq!(
+            labelled_point2::<usize>("x=3, y=4"),
+            Ok(("", Point2::new(3, 4)))
+        );
+        assert!(labelled_point2::<isize>("x=-3, y=4").is_ok());
+        // too big for a 32-bit usize, rather than wrapped into one
+        #[cfg(target_pointer_width = "32")]
+        assert!(labelled_point2::<usize>("x=4294967296, y=0").is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use crate::error::{AocError, Result};
+use nom::{
+    character::complete::{line_ending, satisfy},
+    combinator::{map_opt, verify},
+    multi::{many1, separated_list1},
+    IResult,
+};
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+/// A position in a [`Grid`] as `(x, y)`, with `y` counting rows down from the
+/// top.
+pub type Pos = (usize, usize);
+
+const OFFSETS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const OFFSETS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows, or returns the index of the first row whose
+    /// length differs from the first.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, usize> {
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(ragged) = rows.iter().position(|row| row.len() != width) {
+            return Err(ragged);
+        }
+        let height = rows.len();
+        Ok(Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses one cell per character, one row per line.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let mut rows = vec![];
+        for line in input.lines() {
+            let row = line
+                .char_indices()
+                .map(|(at, c)| {
+                    cell(c).ok_or_else(|| {
+                        AocError::parse(input, &line[at..], format!("unexpected `{c}`"))
+                    })
+                })
+                .collect::<Result<Vec<T>>>()?;
+            rows.push(row);
+        }
+        if rows.is_empty() {
+            return Err(AocError::parse(input, input, "expected a grid"));
+        }
+
+        Self::from_rows(rows).map_err(|ragged| {
+            let line = input.lines().nth(ragged).unwrap_or_default();
+            AocError::parse(input, line, "expected every row to be the same width")
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.1 * self.width + pos.0])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.1 * self.width + pos.0])
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells in row `y`, or none if it is outside the grid.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        let row = (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width]);
+        row.unwrap_or_default().iter()
+    }
+
+    /// The cells in column `x`, or none if it is outside the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let from = if x < self.width { x } else { self.cells.len() };
+        self.cells[from..].iter().step_by(self.width.max(1))
+    }
+
+    /// The positions reached by stepping from `pos` by `(dx, dy)` until
+    /// leaving the grid, not including `pos` itself.
+    pub fn ray(&self, pos: Pos, (dx, dy): (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(Some(pos), move |&pos| self.offset(pos, (dx, dy))).skip(1)
+    }
+
+    /// The orthogonal neighbours of `pos` that are inside the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        OFFSETS4
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    /// The orthogonal and diagonal neighbours of `pos` that are inside the
+    /// grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        OFFSETS8
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    fn offset(&self, (x, y): Pos, (dx, dy): (isize, isize)) -> Option<Pos> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.position(|cell| cell == value)
+    }
+
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rebuild(self.height, self.width, |(x, y)| (y, x))
+    }
+
+    /// Rotates the grid a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.rebuild(self.height, self.width, |(x, y)| (y, height - 1 - x))
+    }
+
+    /// Rotates the grid a quarter turn anticlockwise.
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.rebuild(self.height, self.width, |(x, y)| (width - 1 - y, x))
+    }
+
+    /// Builds a `width` by `height` grid whose cell at `pos` is this grid's cell
+    /// at `source(pos)`.
+    fn rebuild(&self, width: usize, height: usize, source: impl Fn(Pos) -> Pos) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|pos| self[source(pos)].clone())
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a {width}x{height} grid"))
+    }
+}
+
+/// Renders each row on its own line.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A nom parser for a grid embedded in a larger input, one cell per character
+/// and one row per line.
+pub fn grid<'a, T>(
+    mut cell: impl FnMut(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Grid<T>> {
+    move |input| {
+        let row = many1(map_opt(satisfy(|c| c != '\n' && c != '\r'), &mut cell));
+        let (input, rows) = verify(separated_list1(line_ending, row), |rows: &Vec<Vec<T>>| {
+            rows.iter().all(|row| row.len() == rows[0].len())
+        })(input)?;
+        let grid = Grid::from_rows(rows).unwrap_or_else(|_| unreachable!("rows are verified"));
+        Ok((input, grid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456\n", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parses_and_walks_a_grid() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.find(&5), Some((1, 1)));
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.row(1).copied().collect::<Vec<_>>(), vec![4, 5, 6]);
+        assert_eq!(grid.column(4).count(), 0);
+        assert_eq!(grid.row(2).count(), 0);
+        assert_eq!(
+            grid.ray((0, 0), (1, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 0)]
+        );
+        assert_eq!(grid.neighbors4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbors8((1, 0)).count(), 5);
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        let err = Grid::parse("12\n3x", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "unexpected `x` at line 2, column 2: `x`");
+        let err = Grid::parse("12\n3", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected every row to be the same width at line 2, column 1: `3`"
+        );
+    }
+
+    #[test]
+    fn transposes_and_rotates() {
+        let digits = digits();
+        assert_eq!(digits.transpose().to_string(), "14\n25\n36\n");
+        assert_eq!(digits.rotate_cw().to_string(), "41\n52\n63\n");
+        assert_eq!(digits.rotate_ccw().to_string(), "36\n25\n14\n");
+        assert_eq!(digits.rotate_cw().rotate_ccw(), digits);
+
+        let (rest, parsed) = grid(|c: char| c.to_digit(10))("123\n456\n\nmore").unwrap();
+        assert_eq!(parsed, digits);
+        assert_eq!(rest, "\n\nmore");
+    }
+}
@@ -0,0 +1,32 @@
+use std::borrow::Cow;
+
+/// Cleans up puzzle input the way an editor might have left it.
+///
+/// Strips a leading byte order mark and any trailing whitespace, including
+/// the final newline, and turns `\r\n` line endings into `\n`. Leading and
+/// inner whitespace is kept, since some inputs line things up with it.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input).trim_end();
+    if input.contains("\r\n") {
+        Cow::Owned(input.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_line_endings_and_trailing_whitespace() {
+        assert_eq!(normalize("\u{feff}1\r\n\r\n2\r\n"), "1\n\n2");
+        assert_eq!(normalize("    [D]\n 1 \n\n  \n"), "    [D]\n 1");
+    }
+
+    #[test]
+    fn borrows_clean_input() {
+        assert!(matches!(normalize("1\n2\n"), Cow::Borrowed("1\n2")));
+        assert!(matches!(normalize("1\r\n2"), Cow::Owned(_)));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::geometry::Scalar;
+use std::ops::RangeInclusive;
+
+/// The integers from `start` to `end` inclusive, never empty.
+///
+/// Unlike [`RangeInclusive`], every check here is a couple of comparisons,
+/// however wide the interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Scalar> Interval<T> {
+    /// `None` when `end` comes before `start`.
+    pub fn new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    pub fn start(self) -> T {
+        self.start
+    }
+
+    pub fn end(self) -> T {
+        self.end
+    }
+
+    pub fn contains(self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Whether every value in `other` is also in `self`.
+    pub fn covers(self, other: Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(self, other: Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// How many values are in the interval. Overflows `T` for an interval
+    /// over every value of `T`.
+    pub fn width(self) -> T {
+        self.end - self.start + T::ONE
+    }
+
+    /// Whether `other` overlaps or is right next to `self`, so that the two
+    /// make up one interval.
+    fn touches(self, other: Self) -> bool {
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        // `first.end` is below `second.start`, so adding one can't overflow,
+        // where subtracting could for intervals at opposite extremes
+        second.start <= first.end || first.end + T::ONE == second.start
+    }
+
+    fn hull(self, other: Self) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+impl<T> From<Interval<T>> for RangeInclusive<T> {
+    fn from(interval: Interval<T>) -> Self {
+        interval.start..=interval.end
+    }
+}
+
+/// A union of intervals, kept as the fewest disjoint intervals in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { intervals: vec![] }
+    }
+}
+
+impl<T: Scalar> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // the intervals `interval` merges with are a run of them
+        let from = self
+            .intervals
+            .partition_point(|other| other.end < interval.start && !other.touches(interval));
+        let to = from
+            + self.intervals[from..]
+                .partition_point(|other| other.start <= interval.end || other.touches(interval));
+        let merged = self.intervals[from..to]
+            .iter()
+            .fold(interval, |merged, &other| merged.hull(other));
+        self.intervals.splice(from..to, [merged]);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|other| other.end < value);
+        self.intervals
+            .get(index)
+            .is_some_and(|other| other.contains(value))
+    }
+
+    /// How many values are in the set, like [`Interval::width`].
+    pub fn coverage(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |total, interval| total + interval.width())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The disjoint intervals making up the set, in order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Interval<T>> + '_ {
+        self.intervals.iter().copied()
+    }
+}
+
+impl<T: Scalar> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut sorted: Vec<_> = iter.into_iter().collect();
+        sorted.sort_unstable();
+        let mut intervals: Vec<Interval<T>> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if last.touches(interval) => *last = last.hull(interval),
+                _ => intervals.push(interval),
+            }
+        }
+        Self { intervals }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: u64, end: u64) -> Interval<u64> {
+        Interval::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn intervals_compare_without_scanning() {
+        let wide = interval(0, u64::MAX - 1);
+        let narrow = interval(u64::MAX / 2, u64::MAX - 1);
+        assert!(wide.covers(narrow) && !narrow.covers(wide));
+        assert!(narrow.overlaps(wide));
+        assert!(!interval(1, 2).overlaps(interval(3, 4)));
+        assert_eq!(wide.intersection(narrow), Some(narrow));
+        assert_eq!(interval(1, 2).intersection(interval(3, 4)), None);
+        assert_eq!(narrow.width(), u64::MAX / 2 + 1);
+        assert!(wide.contains(7) && !narrow.contains(7));
+        assert_eq!(Interval::new(2, 1), None);
+    }
+
+    #[test]
+    fn sets_merge_overlapping_and_adjacent_intervals() {
+        let collected: IntervalSet<i32> = [(8, 9), (-3, 1), (2, 4), (0, 3), (11, 12)]
+            .into_iter()
+            .map(|(start, end)| Interval::new(start, end).unwrap())
+            .collect();
+        let mut inserted = IntervalSet::new();
+        for interval in collected.iter().rev() {
+            inserted.insert(interval);
+        }
+        inserted.insert(Interval::new(5, 7).unwrap());
+        inserted.insert(Interval::new(20, 20).unwrap());
+
+        let spans = |set: &IntervalSet<i32>| -> Vec<_> {
+            set.iter().map(|i| (i.start(), i.end())).collect()
+        };
+        assert_eq!(spans(&collected), vec![(-3, 4), (8, 9), (11, 12)]);
+        assert_eq!(spans(&inserted), vec![(-3, 9), (11, 12), (20, 20)]);
+        assert_eq!(collected.coverage(), 8 + 2 + 2);
+        assert!(collected.contains(12) && !collected.contains(10) && !collected.contains(13));
+    }
+
+    #[test]
+    fn sets_merge_at_the_extremes_of_t() {
+        let low = Interval::new(i32::MIN, -1).unwrap();
+        let high = Interval::new(i32::MAX, i32::MAX).unwrap();
+        let set: IntervalSet<i32> = [high, low].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![low, high]);
+
+        let mut set = IntervalSet::new();
+        set.insert(Interval::new(1, i32::MAX).unwrap());
+        set.insert(Interval::new(i32::MIN, 0).unwrap());
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![Interval::new(i32::MIN, i32::MAX).unwrap()]
+        );
+        assert!(set.contains(i32::MIN) && set.contains(i32::MAX));
+    }
+}
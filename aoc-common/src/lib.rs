@@ -0,0 +1,31 @@
+//! Code shared between every year's day crates.
+//!
+//! The parsing crates and `tracing` are re-exported so that a day only needs
+//! to depend on `aoc-common` to pick up the same versions as every other day
+//! in the workspace.
+
+pub mod error;
+pub mod examples;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod params;
+pub mod parse;
+pub mod solution;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod viz;
+
+pub use aoc_macros::example_tests;
+pub use error::{AocError, Result};
+pub use geometry::{Direction, Point2, Point3, Turn};
+pub use grid::Grid;
+pub use input::normalize;
+pub use interval::{Interval, IntervalSet};
+pub use itertools;
+pub use nom;
+pub use params::RawParams;
+pub use solution::{Part, Solution};
+pub use tracing;
+pub use viz::{Frame, Viz};
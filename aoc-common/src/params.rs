@@ -0,0 +1,105 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// Extra knobs for a solver, given on the command line as `key=value`.
+///
+/// Most days take no parameters; day 15 of 2022 for instance reads the row it
+/// scans and the size of the search space from here.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RawParams(BTreeMap<String, String>);
+
+impl RawParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.0.insert(key.into(), value.into());
+    }
+
+    /// Returns the parameter parsed as `T`, or `None` when it was not given.
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, ParamError> {
+        self.0
+            .get(key)
+            .map(|value| {
+                value.parse().map_err(|_| ParamError::Invalid {
+                    key: key.to_string(),
+                    value: value.clone(),
+                })
+            })
+            .transpose()
+    }
+
+    /// Returns the parameter parsed as `T`, or `default` when it was not given.
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, ParamError> {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+}
+
+impl FromIterator<(String, String)> for RawParams {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    Malformed(String),
+    Invalid { key: String, value: String },
+    Missing(String),
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Malformed(raw) => write!(f, "expected `key=value`, got `{raw}`"),
+            ParamError::Invalid { key, value } => {
+                write!(f, "invalid value `{value}` for parameter `{key}`")
+            }
+            ParamError::Missing(key) => write!(f, "missing parameter `{key}`"),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+/// Typed parameters of a [`Solution`](crate::Solution), read from the raw
+/// `key=value` pairs with defaults for anything not given.
+pub trait FromParams: Sized {
+    fn from_params(params: &RawParams) -> Result<Self, ParamError>;
+}
+
+impl FromParams for () {
+    fn from_params(_params: &RawParams) -> Result<Self, ParamError> {
+        Ok(())
+    }
+}
+
+/// Parses a single `key=value` pair as given on the command line.
+pub fn parse_pair(raw: &str) -> Result<(String, String), ParamError> {
+    match raw.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(ParamError::Malformed(raw.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_or_falls_back_to_default() {
+        let params: RawParams = [parse_pair("target_row=10").unwrap()].into_iter().collect();
+        assert_eq!(params.get_or("target_row", 2_000_000), Ok(10));
+        assert_eq!(params.get_or("search_space", 20.), Ok(20.));
+        assert_eq!(params.get::<u32>("search_space"), Ok(None));
+    }
+
+    #[test]
+    fn bad_values_are_errors() {
+        let params: RawParams = [parse_pair("target_row=ten").unwrap()]
+            .into_iter()
+            .collect();
+        assert!(params.get_or("target_row", 0).is_err());
+        assert!(parse_pair("target_row").is_err());
+    }
+}
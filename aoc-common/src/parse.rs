@@ -0,0 +1,124 @@
+//! nom combinators for the shapes puzzle inputs keep coming in.
+//!
+//! Lines may end in `\n` or `\r\n`, and [`parse_all`] ignores whitespace left
+//! at the end of the input.
+
+use crate::{
+    error::{from_nom, AocError, Result},
+    geometry::Scalar,
+};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, line_ending, space0},
+    combinator::recognize,
+    error::Error,
+    multi::separated_list1,
+    sequence::{pair, preceded, tuple},
+    IResult, Parser,
+};
+
+pub use crate::geometry::{csv_point3, labelled_point2};
+pub use crate::grid::grid;
+
+/// Runs `parser` over the whole of `input`, failing if anything other than
+/// trailing whitespace is left over.
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, O, Error<&'a str>>,
+) -> Result<O> {
+    let (rest, output) = parser.parse(input).map_err(|err| from_nom(input, err))?;
+    let rest = rest.trim_start_matches(['\r', '\n']);
+    if rest.trim_end().is_empty() {
+        Ok(output)
+    } else {
+        Err(AocError::parse(input, rest, "unexpected input"))
+    }
+}
+
+/// Splits `input` into the runs of lines between blank lines.
+///
+/// Each group is a subslice of `input`, so errors can still point into it.
+pub fn split_groups(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        rest = rest.trim_start_matches(['\r', '\n']);
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest
+            .split_inclusive('\n')
+            .take_while(|line| !line.trim_end_matches(['\r', '\n']).is_empty())
+            .map(str::len)
+            .sum();
+        let (group, tail) = rest.split_at(end);
+        rest = tail;
+        Some(group.trim_end_matches(['\r', '\n']))
+    })
+}
+
+pub fn blank_line(input: &str) -> IResult<&str, &str> {
+    recognize(pair(line_ending, line_ending))(input)
+}
+
+/// One or more `item`s, one per line.
+pub fn lines<'a, O>(
+    item: impl Parser<&'a str, O, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(line_ending, item)
+}
+
+/// One or more `group`s separated by blank lines.
+pub fn groups<'a, O>(
+    group: impl Parser<&'a str, O, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(blank_line, group)
+}
+
+/// A possibly signed decimal number.
+pub fn number<T: Scalar>(input: &str) -> IResult<&str, T> {
+    T::parse(input)
+}
+
+/// One or more numbers separated by `separator`.
+pub fn numbers<'a, T: Scalar, S>(
+    separator: impl Parser<&'a str, S, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>> {
+    separated_list1(separator, T::parse)
+}
+
+/// The `value` of a `key: value` line.
+pub fn labelled<'a, O>(
+    key: &'static str,
+    value: impl Parser<&'a str, O, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(tuple((tag(key), char(':'), space0)), value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_groups_of_lines() {
+        let input = "Items: 1, -2\r\nItems: 3\r\n\r\nItems: 4\n";
+        let items = lines(labelled("Items", numbers::<i32, _>(tag(", "))));
+        assert_eq!(
+            parse_all(input, groups(items)),
+            Ok(vec![vec![vec![1, -2], vec![3]], vec![vec![4]]])
+        );
+
+        let groups: Vec<&str> = split_groups("1\r\n2\r\n\r\n\n3\n").collect();
+        assert_eq!(groups, vec!["1\r\n2", "3"]);
+    }
+
+    #[test]
+    fn parse_all_rejects_leftover_input() {
+        let input = "1,2\n3,x\n";
+        let err = parse_all(input, lines(numbers::<u32, _>(char(',')))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unexpected input at line 2, column 2: `,x`"
+        );
+        assert!(parse_all("", number::<u32>).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{
+    error::Result,
+    params::{FromParams, RawParams},
+    viz::Viz,
+};
+use std::fmt::{self, Display};
+use tracing::{debug, info_span, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = u8;
+
+    fn try_from(part: u8) -> Result<Self, Self::Error> {
+        match part {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            other => Err(other),
+        }
+    }
+}
+
+/// A single day's puzzle.
+///
+/// The input is parsed once into [`Solution::Parsed`] and both parts then run
+/// over that, so neither part pays for parsing twice.
+pub trait Solution {
+    const YEAR: u16;
+    const DAY: u8;
+    /// The example from the puzzle text, used when there is no real input.
+    const EXAMPLE: &'static str;
+
+    type Parsed<'a>;
+    type Params: FromParams;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>>;
+    fn part1(parsed: &Self::Parsed<'_>, params: &Self::Params) -> Result<Self::Answer1>;
+    fn part2(parsed: &Self::Parsed<'_>, params: &Self::Params) -> Result<Self::Answer2>;
+
+    /// Runs `part`, sending frames of its progress to `viz`.
+    ///
+    /// Days that simulate something worth watching override this. The
+    /// default runs the part without any frames.
+    fn visualize(
+        parsed: &Self::Parsed<'_>,
+        params: &Self::Params,
+        part: Part,
+        viz: &mut Viz,
+    ) -> Result<String> {
+        let _ = viz;
+        match part {
+            Part::One => Self::part1(parsed, params).map(|answer| answer.to_string()),
+            Part::Two => Self::part2(parsed, params).map(|answer| answer.to_string()),
+        }
+    }
+}
+
+/// A parsed input with its parameters, ready to have either part run on it.
+///
+/// This erases the types of a [`Solution`] so that days can be stored side by
+/// side in a registry.
+pub trait Prepared {
+    fn part1(&self) -> Result<String>;
+    fn part2(&self) -> Result<String>;
+    fn visualize(&self, part: Part, viz: &mut Viz) -> Result<String>;
+
+    fn part(&self, part: Part) -> Result<String> {
+        match part {
+            Part::One => self.part1(),
+            Part::Two => self.part2(),
+        }
+    }
+}
+
+struct PreparedSolution<'a, S: Solution> {
+    parsed: S::Parsed<'a>,
+    params: S::Params,
+}
+
+impl<S: Solution> Prepared for PreparedSolution<'_, S> {
+    fn part1(&self) -> Result<String> {
+        solve::<S>(Part::One, || {
+            S::part1(&self.parsed, &self.params).map(|answer| answer.to_string())
+        })
+    }
+
+    fn part2(&self) -> Result<String> {
+        solve::<S>(Part::Two, || {
+            S::part2(&self.parsed, &self.params).map(|answer| answer.to_string())
+        })
+    }
+
+    fn visualize(&self, part: Part, viz: &mut Viz) -> Result<String> {
+        solve::<S>(part, || S::visualize(&self.parsed, &self.params, part, viz))
+    }
+}
+
+/// Runs a part of `S` in a `solve` span, logging how it went.
+fn solve<S: Solution>(part: Part, run: impl FnOnce() -> Result<String>) -> Result<String> {
+    let _span = info_span!("solve", year = S::YEAR, day = S::DAY, %part).entered();
+    let answer = run();
+    match &answer {
+        Ok(answer) => debug!(%answer, "solved"),
+        Err(err) => warn!(%err, "failed"),
+    }
+    answer
+}
+
+/// Reads the parameters and parses the input of `S`.
+pub fn prepare<'a, S>(input: &'a str, params: &RawParams) -> Result<Box<dyn Prepared + 'a>>
+where
+    S: Solution + 'static,
+    S::Params: 'static,
+{
+    let _span = info_span!("parse", year = S::YEAR, day = S::DAY, bytes = input.len()).entered();
+    let params = S::Params::from_params(params).inspect_err(|err| warn!(%err, "bad params"))?;
+    let parsed = S::parse(input).inspect_err(|err| warn!(%err, "failed"))?;
+    Ok(Box::new(PreparedSolution::<S> { parsed, params }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AocError;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const YEAR: u16 = 2000;
+        const DAY: u8 = 1;
+        const EXAMPLE: &'static str = "1\n2\n3";
+
+        type Parsed<'a> = Vec<u32>;
+        type Params = ();
+        type Answer1 = u32;
+        type Answer2 = usize;
+
+        fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+            input
+                .lines()
+                .map(|line| {
+                    line.parse()
+                        .map_err(|_| AocError::parse(input, line, "expected a number"))
+                })
+                .collect()
+        }
+
+        fn part1(parsed: &Self::Parsed<'_>, _params: &()) -> Result<u32> {
+            Ok(parsed.iter().sum())
+        }
+
+        fn part2(parsed: &Self::Parsed<'_>, _params: &()) -> Result<usize> {
+            Ok(parsed.len())
+        }
+    }
+
+    #[test]
+    fn prepared_runs_both_parts() {
+        let prepared = prepare::<Sum>("1\n2\n3", &RawParams::new()).unwrap();
+        assert_eq!(prepared.part(Part::One), Ok("6".to_string()));
+        assert_eq!(prepared.part(Part::Two), Ok("3".to_string()));
+    }
+
+    #[test]
+    fn prepare_reports_parse_errors() {
+        let Err(err) = prepare::<Sum>("1\nx\n3", &RawParams::new()) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            err.to_string(),
+            "expected a number at line 2, column 1: `x`"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Property testing for the day crates, behind the `testing` feature.
+//!
+//! The strategies generate puzzle-shaped inputs as text, so they exercise a
+//! day's parser as well as its solver. [`differential_tests!`] fuzzes a part
+//! against a slow but obviously correct reference implementation.
+//!
+//! [`differential_tests!`]: crate::differential_tests
+
+use crate::solution::{Part, Solution};
+use proptest::{collection::vec, prelude::*, sample::select, test_runner::TestCaseError};
+use std::ops::{Range, RangeInclusive};
+
+pub use proptest;
+
+/// Rows of `cells`, one line per row, every row the same width.
+pub fn grid(
+    cells: impl Strategy<Value = char> + Clone,
+    width: Range<usize>,
+    height: Range<usize>,
+) -> impl Strategy<Value = String> {
+    (width, height)
+        .prop_flat_map(move |(width, height)| vec(vec(cells.clone(), width), height))
+        .prop_map(|rows| {
+            rows.into_iter()
+                .map(String::from_iter)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+}
+
+/// Lines of two `start-end` ranges within `0..=max`, like `2-4,6-8`.
+pub fn range_pairs(max: u32, lines: Range<usize>) -> impl Strategy<Value = String> {
+    let range = (0..=max, 0..=max).prop_map(|(a, b)| format!("{}-{}", a.min(b), a.max(b)));
+    vec((range.clone(), range), lines).prop_map(|pairs| {
+        pairs
+            .into_iter()
+            .map(|(a, b)| format!("{a},{b}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// Lines of `Sensor at x=.., y=..: closest beacon is at x=.., y=..` with
+/// every coordinate in `coords`.
+pub fn sensor_beacons(
+    coords: RangeInclusive<i32>,
+    sensors: Range<usize>,
+) -> impl Strategy<Value = String> {
+    let point = (coords.clone(), coords);
+    vec((point.clone(), point), sensors).prop_map(|pairs| {
+        pairs
+            .into_iter()
+            .map(|((sx, sy), (bx, by))| {
+                format!("Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// Lines of a direction and a step count, like `R 4`.
+pub fn rope_moves(
+    steps: RangeInclusive<u32>,
+    moves: Range<usize>,
+) -> impl Strategy<Value = String> {
+    vec((select(vec!['L', 'R', 'U', 'D']), steps), moves).prop_map(|moves| {
+        moves
+            .into_iter()
+            .map(|(dir, steps)| format!("{dir} {steps}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// Checks that `part` of `S` gives the same answer as `reference` on
+/// `input`, which must parse.
+pub fn differential<S: Solution>(
+    input: &str,
+    part: Part,
+    params: &S::Params,
+    reference: impl Fn(&S::Parsed<'_>, &S::Params) -> crate::Result<String>,
+) -> Result<(), TestCaseError> {
+    let parsed =
+        S::parse(input).map_err(|err| TestCaseError::fail(format!("{err} in\n{input}")))?;
+    let actual = match part {
+        Part::One => S::part1(&parsed, params).map(|answer| answer.to_string()),
+        Part::Two => S::part2(&parsed, params).map(|answer| answer.to_string()),
+    };
+    prop_assert_eq!(
+        actual,
+        reference(&parsed, params),
+        "part {} of\n{}",
+        part,
+        input
+    );
+    Ok(())
+}
+
+/// Generates a property test per part comparing a solution against a
+/// reference implementation on inputs from a strategy.
+///
+/// A reference takes the parsed input and params like the part it checks.
+/// Params default to `Default::default()`.
+///
+/// ```ignore
+/// differential_tests! {
+///     Day04;
+///     part1_matches_scan: part1 => scan_part1, range_pairs(50, 1..20);
+/// }
+/// ```
+#[macro_export]
+macro_rules! differential_tests {
+    ($solution:ty; $($name:ident: $part:ident => $reference:expr, $strategy:expr;)+) => {
+        $crate::differential_tests! {
+            $solution, params = ::core::default::Default::default();
+            $($name: $part => $reference, $strategy;)+
+        }
+    };
+    ($solution:ty, params = $params:expr; $($name:ident: $part:ident => $reference:expr, $strategy:expr;)+) => {
+        $(
+            #[test]
+            fn $name() {
+                let params: <$solution as $crate::Solution>::Params = $params;
+                $crate::testing::proptest::proptest!(|(input in $strategy)| {
+                    $crate::testing::differential::<$solution>(
+                        &input,
+                        $crate::differential_tests!(@part $part),
+                        &params,
+                        |parsed, params| $reference(parsed, params).map(|answer| answer.to_string()),
+                    )?;
+                });
+            }
+        )+
+    };
+    (@part part1) => { $crate::Part::One };
+    (@part part2) => { $crate::Part::Two };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::{lines, parse_all},
+        AocError, Result,
+    };
+    use proptest::{strategy::ValueTree, test_runner::TestRunner};
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const YEAR: u16 = 2000;
+        const DAY: u8 = 1;
+        const EXAMPLE: &'static str = "1\n2";
+
+        type Parsed<'a> = Vec<u32>;
+        type Params = ();
+        type Answer1 = u32;
+        type Answer2 = u32;
+
+        fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+            parse_all(input, lines(crate::parse::number::<u32>))
+        }
+
+        fn part1(numbers: &Self::Parsed<'_>, _params: &()) -> Result<u32> {
+            Ok(numbers.iter().sum())
+        }
+
+        fn part2(numbers: &Self::Parsed<'_>, _params: &()) -> Result<u32> {
+            numbers
+                .iter()
+                .max()
+                .copied()
+                .ok_or_else(|| AocError::unsolvable("no numbers"))
+        }
+    }
+
+    fn sample(strategy: impl Strategy<Value = String>) -> String {
+        strategy
+            .new_tree(&mut TestRunner::deterministic())
+            .unwrap()
+            .current()
+    }
+
+    #[test]
+    fn strategies_generate_puzzle_shaped_input() {
+        let trees = sample(grid(proptest::char::range('0', '9'), 3..4, 2..3));
+        assert_eq!(trees.lines().map(str::len).collect::<Vec<_>>(), vec![3, 3]);
+
+        let pairs = sample(range_pairs(9, 2..3));
+        assert!(pairs.lines().all(|line| {
+            let (a, b) = line.split_once(',').unwrap();
+            [a, b].iter().all(|range| {
+                let (start, end) = range.split_once('-').unwrap();
+                start.parse::<u32>().unwrap() <= end.parse().unwrap()
+            })
+        }));
+
+        let moves = sample(rope_moves(1..=3, 4..5));
+        assert_eq!(moves.lines().count(), 4);
+        assert!(sample(sensor_beacons(-5..=5, 1..2)).starts_with("Sensor at x="));
+    }
+
+    #[test]
+    fn differential_reports_disagreements() {
+        let sum = |numbers: &Vec<u32>, _: &()| Ok(numbers.iter().sum::<u32>().to_string());
+        assert!(differential::<Sum>("1\n2", Part::One, &(), sum).is_ok());
+        assert!(differential::<Sum>("1\n2", Part::Two, &(), sum).is_err());
+        assert!(differential::<Sum>("x", Part::One, &(), sum).is_err());
+    }
+}
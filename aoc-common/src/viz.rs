@@ -0,0 +1,394 @@
+//! Frames of a simulation's state, for watching a solver work.
+//!
+//! A solver takes a [`Viz`] and hands it a closure building each [`Frame`].
+//! The closure only runs when something is rendering, so a solver run with
+//! [`Viz::off`] pays next to nothing for its frames.
+
+use crate::{grid::Pos, Grid, Point2};
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    ops::ControlFlow,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Colour {
+    pub const ALL: [Colour; 6] = [
+        Colour::Red,
+        Colour::Green,
+        Colour::Yellow,
+        Colour::Blue,
+        Colour::Magenta,
+        Colour::Cyan,
+    ];
+
+    /// The ANSI SGR code for this as a foreground colour.
+    pub fn ansi(self) -> u8 {
+        31 + self as u8
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub glyph: char,
+    pub colour: Option<Colour>,
+}
+
+impl Cell {
+    pub fn new(glyph: char, colour: Colour) -> Self {
+        Self {
+            glyph,
+            colour: Some(colour),
+        }
+    }
+}
+
+impl From<char> for Cell {
+    fn from(glyph: char) -> Self {
+        Self {
+            glyph,
+            colour: None,
+        }
+    }
+}
+
+/// A snapshot of a grid, some of its cells highlighted, with a caption.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub cells: Grid<Cell>,
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(glyphs: &Grid<char>) -> Self {
+        Self {
+            cells: glyphs.map(|&glyph| Cell::from(glyph)),
+            caption: String::new(),
+        }
+    }
+
+    /// Lays out cells at arbitrary points on a `background`, cropped to the
+    /// smallest rectangle holding them all. Later points draw over earlier
+    /// ones.
+    pub fn from_points(
+        points: impl IntoIterator<Item = (Point2<i32>, Cell)>,
+        background: char,
+    ) -> Self {
+        let points: Vec<_> = points.into_iter().collect();
+        let min = |axis: fn(&Point2<i32>) -> i32| points.iter().map(|(p, _)| axis(p)).min();
+        let max = |axis: fn(&Point2<i32>) -> i32| points.iter().map(|(p, _)| axis(p)).max();
+        let (Some(left), Some(top), Some(right), Some(bottom)) =
+            (min(|p| p.x), min(|p| p.y), max(|p| p.x), max(|p| p.y))
+        else {
+            return Self::new(&Grid::new(0, 0, background));
+        };
+        Self::from_points_in(
+            Point2::new(left, top),
+            Point2::new(right, bottom),
+            points,
+            background,
+        )
+    }
+
+    /// Lays out cells at arbitrary points on a `background` running from
+    /// `top_left` to `bottom_right` inclusive, so a scene keeps its place from
+    /// one frame to the next. Points outside it are left out.
+    pub fn from_points_in(
+        top_left: Point2<i32>,
+        bottom_right: Point2<i32>,
+        points: impl IntoIterator<Item = (Point2<i32>, Cell)>,
+        background: char,
+    ) -> Self {
+        let size = |from: i32, to: i32| usize::try_from(i64::from(to) - i64::from(from) + 1);
+        let (Ok(width), Ok(height)) = (
+            size(top_left.x, bottom_right.x),
+            size(top_left.y, bottom_right.y),
+        ) else {
+            return Self::new(&Grid::new(0, 0, background));
+        };
+
+        let mut cells = Grid::new(width, height, Cell::from(background));
+        for (point, cell) in points {
+            let offset = |at: i32, from: i32| usize::try_from(i64::from(at) - i64::from(from));
+            if let (Ok(x), Ok(y)) = (offset(point.x, top_left.x), offset(point.y, top_left.y)) {
+                if let Some(slot) = cells.get_mut((x, y)) {
+                    *slot = cell;
+                }
+            }
+        }
+        Self {
+            cells,
+            caption: String::new(),
+        }
+    }
+
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    /// Colours the cell at `pos`, if it is in the frame.
+    pub fn highlight(mut self, pos: Pos, colour: Colour) -> Self {
+        if let Some(cell) = self.cells.get_mut(pos) {
+            cell.colour = Some(colour);
+        }
+        self
+    }
+
+    /// Renders the frame with ANSI colours, one `\r\n`-terminated line per
+    /// row, cropped to `width` by `height`.
+    ///
+    /// A frame too big to fit is cropped around its first highlighted cell,
+    /// which is usually whatever is moving.
+    pub fn ansi(&self, width: usize, height: usize) -> String {
+        let focus = self
+            .cells
+            .iter()
+            .find(|(_, cell)| cell.colour.is_some())
+            .map_or((0, 0), |(pos, _)| pos);
+        let origin = |focus: usize, size: usize, view: usize| {
+            focus
+                .saturating_sub(view / 2)
+                .min(size.saturating_sub(view))
+        };
+        let left = origin(focus.0, self.cells.width(), width);
+        let top = origin(focus.1, self.cells.height(), height);
+
+        let mut out = String::new();
+        for y in top..(top + height).min(self.cells.height()) {
+            let mut colour = None;
+            for x in left..(left + width).min(self.cells.width()) {
+                let cell = self.cells[(x, y)];
+                if cell.colour != colour {
+                    match cell.colour {
+                        Some(c) => write!(out, "\x1b[1;{}m", c.ansi()).unwrap(),
+                        None => out.push_str("\x1b[0m"),
+                    }
+                    colour = cell.colour;
+                }
+                out.push(cell.glyph);
+            }
+            if colour.is_some() {
+                out.push_str("\x1b[0m");
+            }
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
+
+/// Renders each row on its own line, without colour.
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.cells.map(|cell| cell.glyph).fmt(f)
+    }
+}
+
+/// Somewhere to show frames.
+pub trait Renderer {
+    /// Shows the next frame. Breaking stops any more frames being sent, but
+    /// the solver carries on to its answer.
+    fn render(&mut self, frame: &Frame) -> io::Result<ControlFlow<()>>;
+
+    /// Called once the last frame has been rendered.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes every frame as plain text, for reading through or diffing later.
+pub struct TextLog<W> {
+    out: W,
+    frames: usize,
+}
+
+impl<W: Write> TextLog<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, frames: 0 }
+    }
+}
+
+impl<W: Write> Renderer for TextLog<W> {
+    fn render(&mut self, frame: &Frame) -> io::Result<ControlFlow<()>> {
+        self.frames += 1;
+        writeln!(self.out, "frame {}: {}", self.frames, frame.caption)?;
+        writeln!(self.out, "{frame}")?;
+        Ok(ControlFlow::Continue(()))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// What a solver sends its frames to.
+pub struct Viz<'a> {
+    renderer: Option<&'a mut dyn Renderer>,
+    every: usize,
+    seen: usize,
+    rendered: usize,
+    skipped_last: bool,
+    error: Option<io::Error>,
+}
+
+impl<'a> Viz<'a> {
+    pub fn new(renderer: &'a mut dyn Renderer) -> Self {
+        Self {
+            renderer: Some(renderer),
+            every: 1,
+            seen: 0,
+            rendered: 0,
+            skipped_last: false,
+            error: None,
+        }
+    }
+
+    /// Drops every frame without building it.
+    pub fn off() -> Self {
+        Self {
+            renderer: None,
+            every: 1,
+            seen: 0,
+            rendered: 0,
+            skipped_last: false,
+            error: None,
+        }
+    }
+
+    /// Only renders every `n`th frame, starting with the first.
+    pub fn every(mut self, n: usize) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.renderer.is_some()
+    }
+
+    pub fn frame(&mut self, frame: impl FnOnce() -> Frame) {
+        if !self.is_on() {
+            return;
+        }
+        self.seen += 1;
+        self.skipped_last = !(self.seen - 1).is_multiple_of(self.every);
+        if !self.skipped_last {
+            self.render(frame());
+        }
+    }
+
+    /// Renders the final state, unless the last [`Viz::frame`] already
+    /// showed it.
+    pub fn last_frame(&mut self, frame: impl FnOnce() -> Frame) {
+        if self.skipped_last {
+            self.skipped_last = false;
+            self.render(frame());
+        }
+    }
+
+    fn render(&mut self, frame: Frame) {
+        let Some(renderer) = self.renderer.as_mut() else {
+            return;
+        };
+        match renderer.render(&frame) {
+            Ok(ControlFlow::Continue(())) => self.rendered += 1,
+            Ok(ControlFlow::Break(())) => self.renderer = None,
+            Err(err) => {
+                self.error = Some(err);
+                self.renderer = None;
+            }
+        }
+    }
+
+    /// The number of frames rendered, or the error that stopped rendering.
+    pub fn finish(self) -> io::Result<usize> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.rendered),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Captions(Vec<String>);
+
+    impl Renderer for Captions {
+        fn render(&mut self, frame: &Frame) -> io::Result<ControlFlow<()>> {
+            self.0.push(frame.caption.clone());
+            Ok(if self.0.len() < 4 {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            })
+        }
+    }
+
+    fn numbered(n: usize) -> impl FnOnce() -> Frame {
+        move || Frame::new(&Grid::new(1, 1, '.')).caption(n.to_string())
+    }
+
+    #[test]
+    fn viz_skips_frames_and_stops_when_asked() {
+        let mut off = Viz::off();
+        off.frame(|| panic!("frames aren't built with nothing to render"));
+        assert_eq!(off.finish().unwrap(), 0);
+
+        let mut captions = Captions::default();
+        let mut viz = Viz::new(&mut captions).every(3);
+        (0..5).for_each(|n| viz.frame(numbered(n)));
+        viz.last_frame(numbered(4));
+        (5..7).for_each(|n| viz.frame(numbered(n)));
+        viz.last_frame(numbered(6));
+        (7..20).for_each(|n| viz.frame(numbered(n)));
+        assert!(!viz.is_on());
+        assert_eq!(viz.finish().unwrap(), 3);
+        assert_eq!(captions.0, vec!["0", "3", "4", "6"]);
+    }
+
+    #[test]
+    fn frames_render_as_text_and_ansi() {
+        let frame = Frame::from_points(
+            [
+                (Point2::new(-1, 2), Cell::from('#')),
+                (Point2::new(1, 3), Cell::new('H', Colour::Red)),
+            ],
+            '.',
+        )
+        .caption("step 1");
+        assert_eq!(frame.to_string(), "#..\n..H\n");
+        assert_eq!(frame.ansi(3, 1), "..\x1b[1;31mH\x1b[0m\r\n");
+        assert_eq!(frame.ansi(2, 2), "..\r\n.\x1b[1;31mH\x1b[0m\r\n");
+
+        let mut log = TextLog::new(vec![]);
+        assert!(log.render(&frame).unwrap().is_continue());
+        assert_eq!(
+            String::from_utf8(log.out).unwrap(),
+            "frame 1: step 1\n#..\n..H\n\n"
+        );
+    }
+
+    #[test]
+    fn frames_can_keep_a_fixed_origin() {
+        let points = [
+            (Point2::new(0, 0), Cell::from('s')),
+            (Point2::new(1, -1), Cell::from('H')),
+            (Point2::new(5, 0), Cell::from('#')),
+        ];
+        let frame = Frame::from_points_in(Point2::new(-1, -1), Point2::new(2, 0), points, '.');
+        assert_eq!(frame.to_string(), "..H.\n.s..\n");
+        let empty = Frame::from_points_in(Point2::new(1, 0), Point2::new(0, 0), points, '.');
+        assert_eq!(empty.cells.width(), 0);
+    }
+}
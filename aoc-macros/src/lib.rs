@@ -0,0 +1,235 @@
+//! Procedural macros for the day crates, re-exported by `aoc-common`.
+
+use proc_macro::TokenStream;
+use std::{fs, path::Path};
+use toml::{Table, Value};
+
+/// Generates a test for each part of each example in the calling crate's
+/// `examples/` directory.
+///
+/// Every `examples/<name>.txt` needs a `[<name>]` table in
+/// `examples/answers.toml` holding the expected `part1` and `part2`, at least
+/// one of which must be given, and optionally solver `params`. While a day is
+/// unfinished, `ignore = "<reason>"` marks the example's tests `#[ignore]`,
+/// and `cargo test` prints the reason next to each of them:
+///
+/// ```toml
+/// [15-a]
+/// part1 = 26
+/// params = { target_row = 10 }
+/// ```
+///
+/// A crate without an `examples/` directory gets no tests.
+#[proc_macro]
+pub fn example_tests(input: TokenStream) -> TokenStream {
+    let solution = input.to_string();
+    let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let code = match expand(&Path::new(&dir).join("examples"), solution.trim()) {
+        Ok(code) => code,
+        Err(err) => format!("compile_error!({err:?});"),
+    };
+    code.parse().expect("generated code is valid Rust")
+}
+
+#[derive(Debug, PartialEq)]
+struct Example {
+    name: String,
+    part: u8,
+    expected: String,
+    params: Vec<(String, String)>,
+    ignore: Option<String>,
+}
+
+fn expand(dir: &Path, solution: &str) -> Result<String, String> {
+    // Naming the solution keeps a crate with no examples yet compiling.
+    let mut code = format!(
+        "const _: ::core::marker::PhantomData<{solution}> = ::core::marker::PhantomData;\n"
+    );
+    if !dir.exists() {
+        return Ok(code);
+    }
+    // Including the manifest rebuilds the tests when it changes.
+    code += &format!(
+        "const _: &str = include_str!({:?});\n",
+        dir.join("answers.toml").display()
+    );
+    for example in examples(dir)? {
+        let test = format!("example_{}_part{}", example.name, example.part)
+            .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+            .to_lowercase();
+        let input = dir.join(format!("{}.txt", example.name));
+        let part = if example.part == 1 { "One" } else { "Two" };
+        let params: String = example
+            .params
+            .iter()
+            .map(|(key, value)| format!("({key:?}, {value:?}), "))
+            .collect();
+        let ignore = match &example.ignore {
+            Some(reason) => format!("#[ignore = {reason:?}]\n"),
+            None => String::new(),
+        };
+        code += &format!(
+            "#[test]\n{ignore}fn {test}() {{\n    ::aoc_common::examples::check::<{solution}>(include_str!({:?}), ::aoc_common::Part::{part}, {:?}, &[{params}]);\n}}\n",
+            input.display(),
+            example.expected,
+        );
+    }
+    Ok(code)
+}
+
+/// Reads the manifest and checks that it and the inputs match up.
+fn examples(dir: &Path) -> Result<Vec<Example>, String> {
+    let manifest = dir.join("answers.toml");
+    let raw =
+        fs::read_to_string(&manifest).map_err(|err| format!("{}: {err}", manifest.display()))?;
+    let table: Table = raw
+        .parse()
+        .map_err(|err: toml::de::Error| format!("{}: {err}", manifest.display()))?;
+
+    let inputs = fs::read_dir(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+    for entry in inputs.flatten() {
+        let path = entry.path();
+        let name = path.file_stem().and_then(|stem| stem.to_str());
+        if let (Some("txt"), Some(name)) = (path.extension().and_then(|ext| ext.to_str()), name) {
+            if !table.contains_key(name) {
+                return Err(format!("`{name}.txt` has no `[{name}]` in answers.toml"));
+            }
+        }
+    }
+
+    let mut examples = vec![];
+    for (name, answers) in &table {
+        if !dir.join(format!("{name}.txt")).exists() {
+            return Err(format!("`[{name}]` in answers.toml has no `{name}.txt`"));
+        }
+        let answers = answers
+            .as_table()
+            .ok_or_else(|| format!("`{name}` must be a table"))?;
+        let params = match answers.get("params") {
+            None => vec![],
+            Some(Value::Table(params)) => params
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), scalar(value, name)?)))
+                .collect::<Result<_, String>>()?,
+            Some(_) => return Err(format!("`{name}.params` must be a table")),
+        };
+        let ignore = match answers.get("ignore") {
+            None => None,
+            Some(Value::String(reason)) if !reason.is_empty() => Some(reason.clone()),
+            Some(_) => return Err(format!("`{name}.ignore` must give a reason")),
+        };
+        let before = examples.len();
+        for (key, answer) in answers {
+            let part = match key.as_str() {
+                "part1" => 1,
+                "part2" => 2,
+                "params" | "ignore" => continue,
+                other => return Err(format!("`{name}`: unknown key `{other}`")),
+            };
+            examples.push(Example {
+                name: name.clone(),
+                part,
+                expected: scalar(answer, name)?,
+                params: params.clone(),
+                ignore: ignore.clone(),
+            });
+        }
+        if examples.len() == before {
+            return Err(format!(
+                "`[{name}]` in answers.toml has no `part1` or `part2`"
+            ));
+        }
+    }
+    Ok(examples)
+}
+
+fn scalar(value: &Value, name: &str) -> Result<String, String> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Integer(value) => Ok(value.to_string()),
+        Value::Float(value) => Ok(value.to_string()),
+        _ => Err(format!(
+            "`{name}`: answers and params must be strings or numbers"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str, manifest: &str, inputs: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-macros-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("answers.toml"), manifest).unwrap();
+        for input in inputs {
+            fs::write(dir.join(format!("{input}.txt")), "1\n2\n").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn reads_a_test_per_part() {
+        let dir = fixture(
+            "read",
+            "[01-a]\npart1 = 3\npart2 = \"x\"\n\n[01-b]\npart2 = 4\nparams = { rows = 10 }\nignore = \"unfinished\"\n",
+            &["01-a", "01-b"],
+        );
+        let examples = examples(&dir).unwrap();
+        assert_eq!(examples.len(), 3);
+        assert_eq!(
+            examples[2],
+            Example {
+                name: "01-b".to_string(),
+                part: 2,
+                expected: "4".to_string(),
+                params: vec![("rows".to_string(), "10".to_string())],
+                ignore: Some("unfinished".to_string()),
+            }
+        );
+
+        let code = expand(&dir, "Day01").unwrap();
+        assert!(code.contains("#[test]\nfn example_01_a_part1()"));
+        assert!(code.contains("#[test]\n#[ignore = \"unfinished\"]\nfn example_01_b_part2()"));
+        assert!(code.contains("::aoc_common::examples::check::<Day01>("));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn inputs_and_answers_must_match_up() {
+        let dir = fixture("unlisted", "[01-a]\npart1 = 3\n", &["01-a", "01-b"]);
+        assert_eq!(
+            examples(&dir).unwrap_err(),
+            "`01-b.txt` has no `[01-b]` in answers.toml"
+        );
+        fs::remove_dir_all(dir).unwrap();
+
+        let dir = fixture("missing", "[01-a]\npart1 = 3\n", &[]);
+        assert_eq!(
+            examples(&dir).unwrap_err(),
+            "`[01-a]` in answers.toml has no `01-a.txt`"
+        );
+        fs::remove_dir_all(dir).unwrap();
+
+        let dir = fixture("unanswered", "[01-a]\nignore = \"unfinished\"\n", &["01-a"]);
+        assert_eq!(
+            examples(&dir).unwrap_err(),
+            "`[01-a]` in answers.toml has no `part1` or `part2`"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(expand(&dir, "Day01").unwrap().matches("#[test]").count(), 0);
+
+        let dir = fixture(
+            "unexplained",
+            "[01-a]\npart1 = 3\nignore = true\n",
+            &["01-a"],
+        );
+        assert_eq!(
+            examples(&dir).unwrap_err(),
+            "`01-a.ignore` must give a reason"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
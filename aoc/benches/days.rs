@@ -0,0 +1,37 @@
+use aoc::{
+    answers_path,
+    bench::{bench_input, solved_parts},
+    Answers, SOLVERS,
+};
+use aoc_common::RawParams;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn days(c: &mut Criterion) {
+    let answers = Answers::load(&answers_path()).unwrap_or_default();
+    let params = RawParams::new();
+
+    for solver in SOLVERS {
+        let parts = solved_parts(solver, &answers);
+        if parts.is_empty() {
+            continue;
+        }
+        let (input, source) = bench_input(solver);
+        let Ok(prepared) = (solver.prepare)(&input, &params) else {
+            continue;
+        };
+
+        let mut group =
+            c.benchmark_group(format!("{} day {:02} ({source})", solver.year, solver.day));
+        group.sample_size(10);
+        group.bench_function("parse", |b| {
+            b.iter(|| (solver.prepare)(black_box(&input), &params).map(drop))
+        });
+        for part in parts {
+            group.bench_function(format!("part {part}"), |b| b.iter(|| prepared.part(part)));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
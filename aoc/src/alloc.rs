@@ -0,0 +1,167 @@
+//! Counts heap allocations, so that `aoc bench` can show which solvers
+//! allocate heavily.
+//!
+//! [`Counting`] is the global allocator only with the `count-allocs`
+//! feature, since every allocation then pays for the bookkeeping. Counts are
+//! kept per thread, so a measurement only sees the thread it runs on.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Whether the runner was built with the counting allocator.
+pub const COUNTING: bool = cfg!(feature = "count-allocs");
+
+#[derive(Debug, Clone, Copy)]
+struct Counts {
+    allocations: u64,
+    bytes: u64,
+    /// Can dip below zero on a thread that frees what another allocated.
+    current: i64,
+    peak: i64,
+}
+
+thread_local! {
+    static COUNTS: Cell<Counts> = const {
+        Cell::new(Counts {
+            allocations: 0,
+            bytes: 0,
+            current: 0,
+            peak: 0,
+        })
+    };
+}
+
+fn record(update: impl FnOnce(&mut Counts)) {
+    // the thread's counts are gone while it is being torn down
+    let _ = COUNTS.try_with(|counts| {
+        let mut next = counts.get();
+        update(&mut next);
+        next.peak = next.peak.max(next.current);
+        counts.set(next);
+    });
+}
+
+/// The system allocator, counting what passes through it.
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(|counts| {
+                counts.allocations += 1;
+                counts.bytes += layout.size() as u64;
+                counts.current += layout.size() as i64;
+            });
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(|counts| counts.current -= layout.size() as i64);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            record(|counts| {
+                counts.allocations += 1;
+                counts.bytes += new_size as u64;
+                counts.current += new_size as i64 - layout.size() as i64;
+            });
+        }
+        new
+    }
+}
+
+/// What a stretch of code allocated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// Allocations and reallocations made.
+    pub allocations: u64,
+    /// Bytes asked for, counting each reallocation's new size in full.
+    pub bytes: u64,
+    /// The most memory held at once above what was held at the start.
+    pub peak: u64,
+}
+
+/// Runs `f`, along with what it allocated when the counting allocator is in
+/// use.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Usage>) {
+    if COUNTING {
+        let (output, usage) = usage(f);
+        (output, Some(usage))
+    } else {
+        (f(), None)
+    }
+}
+
+fn usage<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let start = COUNTS.with(|counts| {
+        let mut start = counts.get();
+        start.peak = start.current;
+        counts.set(start);
+        start
+    });
+    let output = f();
+    let end = COUNTS.with(Cell::get);
+    let usage = Usage {
+        allocations: end.allocations - start.allocations,
+        bytes: end.bytes - start.bytes,
+        peak: (end.peak - start.current).max(0) as u64,
+    };
+    (output, usage)
+}
+
+/// `bytes` in the largest binary unit that keeps it above one.
+pub fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+    let mut size = bytes as f64 / 1024.;
+    let mut unit = 0;
+    while size >= 1024. && unit < UNITS.len() - 1 {
+        size /= 1024.;
+        unit += 1;
+    }
+    format!("{size:.1}{}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_allocations_and_peak() {
+        let small = Layout::from_size_align(100, 8).unwrap();
+        let big = Layout::from_size_align(1000, 8).unwrap();
+        let ((), usage) = usage(|| unsafe {
+            let a = Counting.alloc(small);
+            Counting.dealloc(a, small);
+            let b = Counting.alloc(big);
+            let b = Counting.realloc(b, big, 1500);
+            let c = Counting.alloc(small);
+            Counting.dealloc(b, Layout::from_size_align(1500, 8).unwrap());
+            Counting.dealloc(c, small);
+        });
+        assert_eq!(
+            usage,
+            Usage {
+                allocations: 4,
+                bytes: 2700,
+                peak: 1600
+            }
+        );
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(human_bytes(512), "512B");
+        assert_eq!(human_bytes(1536), "1.5KiB");
+        assert_eq!(human_bytes(3 * 1024 * 1024), "3.0MiB");
+    }
+}
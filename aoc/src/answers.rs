@@ -0,0 +1,108 @@
+use aoc_common::Part;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use toml::{Table, Value};
+
+/// The checked-in `answers.toml` at the root of the repository.
+pub fn answers_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("answers.toml")
+}
+
+/// Known-good answers for each day's `input.txt`.
+///
+/// The file has a table per day holding `part1` and `part2`, either of which
+/// may be left out until that part is solved:
+///
+/// ```toml
+/// [2022.01]
+/// part1 = 70369
+/// part2 = "203002"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers(BTreeMap<(u16, u8, Part), String>);
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        Self::parse(&raw).map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let table: Table = raw
+            .parse()
+            .map_err(|err: toml::de::Error| err.to_string())?;
+        let mut answers = BTreeMap::new();
+        for (year_key, days) in &table {
+            let year = year_key
+                .parse()
+                .map_err(|_| format!("`{year_key}` is not a year"))?;
+            let days = as_table(days, year_key)?;
+            for (day_key, parts) in days {
+                let at = format!("{year_key}.{day_key}");
+                let day = day_key
+                    .parse()
+                    .map_err(|_| format!("`{at}`: `{day_key}` is not a day"))?;
+                for (part_key, answer) in as_table(parts, &at)? {
+                    let part = match part_key.as_str() {
+                        "part1" => Part::One,
+                        "part2" => Part::Two,
+                        other => return Err(format!("`{at}`: unknown key `{other}`")),
+                    };
+                    let answer = match answer {
+                        Value::String(answer) => answer.clone(),
+                        Value::Integer(answer) => answer.to_string(),
+                        _ => return Err(format!("`{at}.{part_key}` must be a string or integer")),
+                    };
+                    answers.insert((year, day, part), answer);
+                }
+            }
+        }
+        Ok(Self(answers))
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: Part) -> Option<&str> {
+        self.0.get(&(year, day, part)).map(String::as_str)
+    }
+}
+
+fn as_table<'a>(value: &'a Value, at: &str) -> Result<&'a Table, String> {
+    value
+        .as_table()
+        .ok_or_else(|| format!("`{at}` must be a table"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_strings_integers_and_multiline_answers() {
+        let answers = Answers::parse(
+            r#"
+[2022.01]
+part1 = 70369
+
+[2022.10]
+part2 = """
+#..#
+####
+"""
+"#,
+        )
+        .unwrap();
+        assert_eq!(answers.get(2022, 1, Part::One), Some("70369"));
+        assert_eq!(answers.get(2022, 1, Part::Two), None);
+        assert_eq!(answers.get(2022, 10, Part::Two), Some("#..#\n####\n"));
+    }
+
+    #[test]
+    fn rejects_unknown_parts() {
+        let err = Answers::parse("[2022.01]\npart3 = 1").unwrap_err();
+        assert_eq!(err, "`2022.01`: unknown key `part3`");
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    alloc::{self, Usage},
+    input_path, read_input, Answers, Solver,
+};
+use aoc_common::{normalize, Part, RawParams};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSource {
+    Real,
+    Example,
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Real => f.pad("input"),
+            InputSource::Example => f.pad("example"),
+        }
+    }
+}
+
+/// The day's `input.txt`, or its embedded example when that is missing.
+pub fn bench_input(solver: &Solver) -> (String, InputSource) {
+    match read_input(&input_path(solver.year, solver.day)) {
+        Ok(input) => (input, InputSource::Real),
+        Err(_) => (normalize(solver.example).into_owned(), InputSource::Example),
+    }
+}
+
+/// The parts of `solver` worth timing, those with a stored answer.
+///
+/// Unsolved parts either `todo!()` or never return, so they are left out.
+pub fn solved_parts(solver: &Solver, answers: &Answers) -> Vec<Part> {
+    Part::BOTH
+        .into_iter()
+        .filter(|&part| answers.get(solver.year, solver.day, part).is_some())
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Timings {
+    pub parse: Duration,
+    pub parts: Vec<(Part, Duration)>,
+    /// Only counted when the runner is built with `count-allocs`.
+    pub allocations: Option<Allocations>,
+}
+
+/// What parsing and each part allocated in one run.
+#[derive(Debug, Clone)]
+pub struct Allocations {
+    pub parse: Usage,
+    pub parts: Vec<(Part, Usage)>,
+}
+
+impl Allocations {
+    pub fn part(&self, part: Part) -> Option<Usage> {
+        self.parts
+            .iter()
+            .find(|(counted, _)| *counted == part)
+            .map(|(_, usage)| *usage)
+    }
+}
+
+impl Timings {
+    pub fn part(&self, part: Part) -> Option<Duration> {
+        self.parts
+            .iter()
+            .find(|(timed, _)| *timed == part)
+            .map(|(_, elapsed)| *elapsed)
+    }
+}
+
+/// Times parsing and each of `parts` separately, repeating each one until
+/// `budget` has passed and reporting the mean.
+///
+/// Allocations are counted over one more run of each, outside the timing.
+pub fn time(
+    solver: &Solver,
+    input: &str,
+    parts: &[Part],
+    budget: Duration,
+) -> Result<Timings, String> {
+    let params = RawParams::new();
+    let (prepared, parse_usage) = alloc::measure(|| (solver.prepare)(input, &params));
+    let prepared = prepared.map_err(|err| err.to_string())?;
+    let parse = mean(budget, || (solver.prepare)(input, &params).map(drop))
+        .map_err(|err| err.to_string())?;
+
+    let mut part_usage = vec![];
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let (answer, usage) = alloc::measure(|| prepared.part(part).map(drop));
+            part_usage.extend(usage.map(|usage| (part, usage)));
+            answer
+                .and_then(|()| mean(budget, || prepared.part(part).map(drop)))
+                .map(|elapsed| (part, elapsed))
+                .map_err(|err| format!("part {part}: {err}"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let allocations = parse_usage.map(|parse| Allocations {
+        parse,
+        parts: part_usage,
+    });
+    Ok(Timings {
+        parse,
+        parts,
+        allocations,
+    })
+}
+
+fn mean<E>(budget: Duration, mut f: impl FnMut() -> Result<(), E>) -> Result<Duration, E> {
+    let start = Instant::now();
+    let mut runs = 0;
+    while runs == 0 || start.elapsed() < budget {
+        f()?;
+        runs += 1;
+    }
+    Ok(start.elapsed() / runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_only_the_requested_parts() {
+        let solver = crate::find(2022, 1).unwrap();
+        let timings = time(solver, solver.example, &[Part::Two], Duration::ZERO).unwrap();
+        assert!(timings.part(Part::One).is_none());
+        assert!(timings.part(Part::Two).is_some());
+        assert_eq!(timings.allocations.is_some(), alloc::COUNTING);
+        if let Some(allocations) = timings.allocations {
+            assert!(allocations.parse.allocations > 0);
+            assert!(allocations.part(Part::One).is_none());
+            assert!(allocations.part(Part::Two).is_some());
+        }
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let solver = crate::find(2022, 1).unwrap();
+        let err = time(solver, "1\nx", &Part::BOTH, Duration::ZERO).unwrap_err();
+        assert_eq!(err, "expected a calorie count at line 2, column 1: `x`");
+    }
+}
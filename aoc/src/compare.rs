@@ -0,0 +1,176 @@
+//! Runs one day over a directory of inputs, to catch assumptions that only
+//! hold for one of them.
+
+use crate::{read_input, verify::catch, Solver};
+use aoc_common::{Part, RawParams};
+use std::{
+    fs, io,
+    num::NonZeroUsize,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Solvers run on the main thread's stack everywhere else, so the workers get
+/// as much room for deep recursion.
+pub(crate) const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// One input's answers, or why there are none.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub name: String,
+    pub parse: Duration,
+    pub parts: Result<Vec<PartRun>, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+}
+
+impl Run {
+    pub fn failed(&self) -> bool {
+        self.parts
+            .as_ref()
+            .map_or(true, |parts| parts.iter().any(|part| part.answer.is_err()))
+    }
+}
+
+/// Every file in `dir` with its name, [`read_input`] and sorted by name.
+/// Hidden files are skipped.
+pub fn read_inputs(dir: &Path) -> io::Result<Vec<(String, String)>> {
+    let mut inputs = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') || !entry.file_type()?.is_file() {
+            continue;
+        }
+        inputs.push((name, read_input(&entry.path())?));
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Parses each input and runs `parts` of it, spreading the inputs over a
+/// thread per core. Runs come back in the same order as `inputs`.
+///
+/// Timings are taken while other inputs run alongside, so they are only good
+/// for comparing inputs with each other.
+pub fn run(
+    solver: &Solver,
+    inputs: &[(String, String)],
+    params: &RawParams,
+    parts: &[Part],
+) -> Vec<Run> {
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(inputs.len());
+    let next = AtomicUsize::new(0);
+    let runs = Mutex::new(vec![None; inputs.len()]);
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn_scoped(scope, || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((name, input)) = inputs.get(index) else {
+                        break;
+                    };
+                    let run = run_one(solver, name, input, params, parts);
+                    runs.lock().unwrap()[index] = Some(run);
+                })
+                .expect("failed to spawn a worker thread");
+        }
+    });
+
+    runs.into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|run| run.expect("every input is run"))
+        .collect()
+}
+
+fn run_one(solver: &Solver, name: &str, input: &str, params: &RawParams, parts: &[Part]) -> Run {
+    let start = Instant::now();
+    let prepared = catch(|| (solver.prepare)(input, params))
+        .and_then(|prepared| prepared.map_err(|err| err.to_string()));
+    let parse = start.elapsed();
+
+    let parts = prepared.map(|prepared| {
+        parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = catch(|| prepared.part(part))
+                    .and_then(|answer| answer.map_err(|err| err.to_string()));
+                PartRun {
+                    part,
+                    answer,
+                    elapsed: start.elapsed(),
+                }
+            })
+            .collect()
+    });
+    Run {
+        name: name.to_string(),
+        parse,
+        parts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(run: &Run) -> Vec<Result<String, String>> {
+        let parts = run.parts.as_ref().unwrap();
+        parts.iter().map(|part| part.answer.clone()).collect()
+    }
+
+    #[test]
+    fn runs_every_input_in_order() {
+        let solver = crate::find(2022, 1).unwrap();
+        let inputs: Vec<_> = (1..=20)
+            .map(|n| (format!("{n:02}.txt"), format!("{n}\n\n{}", n * 2)))
+            .chain([("bad.txt".to_string(), "1\nx".to_string())])
+            .collect();
+        let runs = run(solver, &inputs, &RawParams::new(), &Part::BOTH);
+
+        assert_eq!(runs.len(), 21);
+        assert_eq!(runs[4].name, "05.txt");
+        assert_eq!(
+            answers(&runs[4]),
+            vec![Ok("10".to_string()), Ok("15".to_string())]
+        );
+        assert!(runs[..20].iter().all(|run| !run.failed()));
+        assert!(runs[20].failed());
+        assert!(runs[20].parts.as_ref().unwrap_err().starts_with("expected"));
+    }
+
+    #[test]
+    fn reads_a_directory_of_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc-compare-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("b.txt"), "2\r\n").unwrap();
+        fs::write(dir.join("a.txt"), "1\n").unwrap();
+        fs::write(dir.join(".hidden"), "3").unwrap();
+
+        let inputs = read_inputs(&dir).unwrap();
+        assert_eq!(
+            inputs,
+            vec![
+                ("a.txt".to_string(), "1".to_string()),
+                ("b.txt".to_string(), "2".to_string())
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,272 @@
+//! Runs every part of many days at once for `aoc run --all`, giving up on
+//! parts that are still running at a deadline.
+
+use crate::{compare::STACK_SIZE, verify::payload_message, Solver};
+use aoc_common::{Part, RawParams};
+use std::{
+    collections::HashMap,
+    fmt,
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A day to run, with its input or why it couldn't be read.
+pub struct Day {
+    pub solver: &'static Solver,
+    pub input: Result<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok(String),
+    /// There was no input, or parsing or solving returned an error.
+    Error(String),
+    Panic(String),
+    /// The part panicked in a `todo!()`.
+    Todo,
+    /// Still running at the deadline. The thread is left to finish, or not,
+    /// in the background.
+    Timeout,
+}
+
+impl Status {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Ok(_) => "ok",
+            Status::Error(_) => "error",
+            Status::Panic(_) => "panic",
+            Status::Todo => "todo",
+            Status::Timeout => "timeout",
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.label())
+    }
+}
+
+/// How one part of one day went.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub status: Status,
+    /// Parsing and solving together, or the deadline after a timeout.
+    pub elapsed: Duration,
+}
+
+struct Job {
+    day: Arc<Day>,
+    part: Part,
+}
+
+enum Event {
+    Started(usize, Instant),
+    Finished(usize, Status, Duration),
+}
+
+/// Parses and runs each of `parts` of every day, spread over a thread per
+/// core. Outcomes come back in the same order as `days`, then `parts`.
+///
+/// Each part parses its own copy of the input, so one part hanging or
+/// panicking never holds up another. A worker stuck past `deadline` can't be
+/// stopped, so it is abandoned and another takes its place.
+pub fn run(days: Vec<Day>, parts: &[Part], deadline: Duration) -> Vec<Outcome> {
+    let jobs: Arc<[Job]> = days
+        .into_iter()
+        .map(Arc::new)
+        .flat_map(|day| {
+            parts.iter().map(move |&part| Job {
+                day: Arc::clone(&day),
+                part,
+            })
+        })
+        .collect();
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(jobs.len());
+    let next = Arc::new(AtomicUsize::new(0));
+    let (events, received) = mpsc::channel();
+    let spawn = || spawn_worker(Arc::clone(&jobs), Arc::clone(&next), events.clone());
+    for _ in 0..workers {
+        spawn();
+    }
+
+    let mut outcomes: Vec<Option<Outcome>> = vec![None; jobs.len()];
+    let outcome = |index: usize, status, elapsed| {
+        let job = &jobs[index];
+        Some(Outcome {
+            year: job.day.solver.year,
+            day: job.day.solver.day,
+            part: job.part,
+            status,
+            elapsed,
+        })
+    };
+    let mut running: HashMap<usize, Instant> = HashMap::new();
+    let mut done = 0;
+    while done < jobs.len() {
+        let event = match running.values().min() {
+            Some(&start) => {
+                received.recv_timeout((start + deadline).saturating_duration_since(Instant::now()))
+            }
+            None => received.recv().map_err(RecvTimeoutError::from),
+        };
+        match event {
+            Ok(Event::Started(index, start)) => {
+                running.insert(index, start);
+            }
+            Ok(Event::Finished(index, status, elapsed)) => {
+                // a part that finishes after timing out keeps its timeout
+                if running.remove(&index).is_some() {
+                    outcomes[index] = outcome(index, status, elapsed);
+                    done += 1;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let now = Instant::now();
+                running.retain(|&index, &mut start| {
+                    if now < start + deadline {
+                        return true;
+                    }
+                    outcomes[index] = outcome(index, Status::Timeout, deadline);
+                    done += 1;
+                    spawn();
+                    false
+                });
+            }
+            Err(RecvTimeoutError::Disconnected) => unreachable!("a sender is kept for new workers"),
+        }
+    }
+
+    outcomes
+        .into_iter()
+        .map(|outcome| outcome.expect("every part is run"))
+        .collect()
+}
+
+fn spawn_worker(jobs: Arc<[Job]>, next: Arc<AtomicUsize>, events: Sender<Event>) {
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(job) = jobs.get(index) else {
+                break;
+            };
+            let start = Instant::now();
+            if events.send(Event::Started(index, start)).is_err() {
+                break;
+            }
+            let status = solve(&job.day, job.part);
+            // nobody is waiting once the run has been given up on
+            if events
+                .send(Event::Finished(index, status, start.elapsed()))
+                .is_err()
+            {
+                break;
+            }
+        })
+        .expect("failed to spawn a worker thread");
+}
+
+fn solve(day: &Day, part: Part) -> Status {
+    let input = match &day.input {
+        Ok(input) => input,
+        Err(err) => return Status::Error(err.clone()),
+    };
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+        (day.solver.prepare)(input, &RawParams::new())?.part(part)
+    }));
+    match answer {
+        Ok(Ok(answer)) => Status::Ok(answer),
+        Ok(Err(err)) => Status::Error(err.to_string()),
+        Err(payload) => {
+            let message = payload_message(&*payload);
+            if message.starts_with("not yet implemented") {
+                Status::Todo
+            } else {
+                Status::Panic(message.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{solution::Prepared, Result};
+
+    fn hang<'a>(_: &'a str, _: &RawParams) -> Result<Box<dyn Prepared + 'a>> {
+        loop {
+            thread::park();
+        }
+    }
+
+    static HANGS: Solver = Solver {
+        year: 2000,
+        day: 1,
+        example: "",
+        prepare: hang,
+    };
+
+    fn statuses(outcomes: &[Outcome]) -> Vec<&str> {
+        outcomes
+            .iter()
+            .map(|outcome| outcome.status.label())
+            .collect()
+    }
+
+    #[test]
+    fn reports_each_part_in_order() {
+        let day = |year, day, input: &str| Day {
+            solver: crate::find(year, day).unwrap(),
+            input: Ok(input.to_string()),
+        };
+        let days = vec![
+            day(2022, 1, "1\n\n2"),
+            day(2022, 16, ""),
+            day(2022, 1, "x"),
+            Day {
+                solver: crate::find(2022, 2).unwrap(),
+                input: Err("no input".to_string()),
+            },
+        ];
+        let outcomes = run(days, &Part::BOTH, Duration::from_secs(10));
+
+        assert_eq!(
+            statuses(&outcomes),
+            ["ok", "ok", "todo", "todo", "error", "error", "error", "error"]
+        );
+        assert_eq!(outcomes[1].status, Status::Ok("3".to_string()));
+        assert_eq!((outcomes[2].day, outcomes[2].part), (16, Part::One));
+        assert_eq!(outcomes[7].status, Status::Error("no input".to_string()));
+    }
+
+    #[test]
+    fn gives_up_on_parts_past_the_deadline() {
+        let days = vec![
+            Day {
+                solver: &HANGS,
+                input: Ok(String::new()),
+            },
+            Day {
+                solver: crate::find(2022, 1).unwrap(),
+                input: Ok("1".to_string()),
+            },
+        ];
+        let deadline = Duration::from_millis(50);
+        let outcomes = run(days, &Part::BOTH, deadline);
+        assert_eq!(statuses(&outcomes), ["timeout", "timeout", "ok", "ok"]);
+        assert_eq!(outcomes[0].elapsed, deadline);
+    }
+}
@@ -0,0 +1,390 @@
+//! Renders frames to PNG files or an animated GIF.
+
+use aoc_common::{
+    viz::{Cell, Colour, Frame, Renderer},
+    Grid,
+};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    ops::ControlFlow,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+pub type Rgb = [u8; 3];
+
+/// The colour each cell is painted in an image.
+///
+/// Highlighted cells take their highlight's colour, others their glyph's.
+/// Glyphs without a colour of their own are painted `foreground`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    pub background: Rgb,
+    pub foreground: Rgb,
+    pub glyphs: HashMap<char, Rgb>,
+    pub highlights: [Rgb; 6],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        let background = [0x0f, 0x0f, 0x23];
+        let mut glyphs = HashMap::from([
+            ('.', background),
+            (' ', background),
+            ('#', [0x80, 0x80, 0x80]),
+            ('o', [0xc2, 0xb2, 0x80]),
+        ]);
+        // elevations from dark green up to white
+        for (i, c) in ('a'..='z').enumerate() {
+            let shade = |from: u8, to: u8| from + ((to - from) as usize * i / 25) as u8;
+            glyphs.insert(c, [shade(0x10, 0xff), shade(0x40, 0xff), shade(0x10, 0xff)]);
+        }
+        Self {
+            background,
+            foreground: [0xcc, 0xcc, 0xcc],
+            glyphs,
+            highlights: [
+                [0xff, 0x40, 0x40],
+                [0x40, 0xff, 0x40],
+                [0xff, 0xff, 0x66],
+                [0x40, 0x80, 0xff],
+                [0xff, 0x40, 0xff],
+                [0x40, 0xff, 0xff],
+            ],
+        }
+    }
+}
+
+impl Palette {
+    pub fn colour(&self, cell: Cell) -> Rgb {
+        match cell.colour {
+            Some(colour) => self.highlights[colour as usize],
+            None => self
+                .glyphs
+                .get(&cell.glyph)
+                .copied()
+                .unwrap_or(self.foreground),
+        }
+    }
+}
+
+/// The default palette with `key=rrggbb` overrides separated by commas,
+/// where a key is a glyph, a highlight colour's name, `background` or
+/// `foreground`, like `#=808080,red=ff0000`.
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut palette = Palette::default();
+        for pair in spec.split(',').filter(|pair| !pair.is_empty()) {
+            let (key, hex) = pair
+                .rsplit_once('=')
+                .ok_or_else(|| format!("expected `key=rrggbb`, got `{pair}`"))?;
+            let rgb = u32::from_str_radix(hex.trim_start_matches('#'), 16)
+                .ok()
+                .filter(|_| hex.trim_start_matches('#').len() == 6)
+                .ok_or_else(|| format!("expected a colour as `rrggbb`, got `{hex}`"))?;
+            let [_, r, g, b] = rgb.to_be_bytes();
+            let highlight = Colour::ALL
+                .into_iter()
+                .find(|colour| format!("{colour:?}").eq_ignore_ascii_case(key));
+
+            match (key, highlight) {
+                ("background", _) => palette.background = [r, g, b],
+                ("foreground", _) => palette.foreground = [r, g, b],
+                (_, Some(colour)) => palette.highlights[colour as usize] = [r, g, b],
+                (glyph, None) => {
+                    let mut chars = glyph.chars();
+                    let (Some(glyph), None) = (chars.next(), chars.next()) else {
+                        return Err(format!("unknown palette key `{key}`"));
+                    };
+                    palette.glyphs.insert(glyph, [r, g, b]);
+                }
+            }
+        }
+        Ok(palette)
+    }
+}
+
+/// Paints `frame` into the top left of a `width` by `height` canvas, in
+/// pixels, `cell_size` pixels to a cell. Cells past the canvas are cut off.
+fn paint<T: Copy>(
+    frame: &Grid<Cell>,
+    cell_size: usize,
+    (width, height): (usize, usize),
+    background: T,
+    colour: impl Fn(Cell) -> T,
+) -> Vec<T> {
+    let mut pixels = vec![background; width * height];
+    for ((x, y), &cell) in frame.iter() {
+        let left = x * cell_size;
+        if left >= width || y * cell_size >= height {
+            continue;
+        }
+        let right = (left + cell_size).min(width);
+        let colour = colour(cell);
+        for row in y * cell_size..((y + 1) * cell_size).min(height) {
+            pixels[row * width + left..row * width + right].fill(colour);
+        }
+    }
+    pixels
+}
+
+fn too_big(width: usize, height: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("a {width}x{height} pixel image is too big, try a smaller cell size"),
+    )
+}
+
+/// Writes each frame to its own numbered PNG in a directory.
+pub struct PngSequence {
+    dir: PathBuf,
+    palette: Palette,
+    cell_size: usize,
+    frames: usize,
+}
+
+impl PngSequence {
+    pub fn new(dir: impl Into<PathBuf>, palette: Palette, cell_size: usize) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            palette,
+            cell_size: cell_size.max(1),
+            frames: 0,
+        })
+    }
+}
+
+impl Renderer for PngSequence {
+    fn render(&mut self, frame: &Frame) -> io::Result<ControlFlow<()>> {
+        self.frames += 1;
+        let width = frame.cells.width() * self.cell_size;
+        let height = frame.cells.height() * self.cell_size;
+        if width == 0 || height == 0 {
+            return Ok(ControlFlow::Continue(()));
+        }
+        let (Ok(png_width), Ok(png_height)) = (u32::try_from(width), u32::try_from(height)) else {
+            return Err(too_big(width, height));
+        };
+
+        let path = self.dir.join(format!("frame-{:05}.png", self.frames));
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(File::create(path)?), png_width, png_height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let pixels = paint(
+            &frame.cells,
+            self.cell_size,
+            (width, height),
+            self.palette.background,
+            |cell| self.palette.colour(cell),
+        );
+        encoder
+            .write_header()?
+            .write_image_data(pixels.as_flattened())?;
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+/// Finds the biggest frame, in cells, to size a [`Gif`] before rendering
+/// for real.
+#[derive(Debug, Default)]
+pub struct Measure {
+    pub size: (usize, usize),
+}
+
+impl Renderer for Measure {
+    fn render(&mut self, frame: &Frame) -> io::Result<ControlFlow<()>> {
+        let (width, height) = self.size;
+        self.size = (
+            width.max(frame.cells.width()),
+            height.max(frame.cells.height()),
+        );
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+/// Encodes frames into an animated GIF as they come.
+///
+/// The GIF is `size` cells big, which should fit the biggest frame, as found
+/// by a [`Measure`] pass. Smaller frames are drawn in its top left corner.
+pub struct Gif<W: Write> {
+    out: Option<W>,
+    encoder: Option<gif::Encoder<W>>,
+    palette: Palette,
+    cell_size: usize,
+    delay: u16,
+    canvas: (usize, usize),
+    /// Each colour's index in the GIF's colour table.
+    indices: HashMap<Rgb, u8>,
+}
+
+impl<W: Write> Gif<W> {
+    pub fn new(
+        out: W,
+        palette: Palette,
+        cell_size: usize,
+        delay: Duration,
+        (columns, rows): (usize, usize),
+    ) -> Self {
+        let cell_size = cell_size.max(1);
+        Self {
+            out: Some(out),
+            encoder: None,
+            palette,
+            cell_size,
+            // in hundredths of a second, and most viewers slow anything under
+            // two down anyway
+            delay: (delay.as_millis() / 10).clamp(2, u16::MAX.into()) as u16,
+            canvas: (
+                columns.saturating_mul(cell_size),
+                rows.saturating_mul(cell_size),
+            ),
+            indices: HashMap::new(),
+        }
+    }
+
+    /// Every colour the palette can paint, which becomes the GIF's global
+    /// colour table, with the background first.
+    fn colours(&self) -> io::Result<Vec<Rgb>> {
+        let palette = &self.palette;
+        let mut colours = vec![palette.background];
+        let all = [palette.foreground]
+            .into_iter()
+            .chain(palette.glyphs.values().copied())
+            .chain(palette.highlights);
+        for rgb in all {
+            if !colours.contains(&rgb) {
+                colours.push(rgb);
+            }
+        }
+        if colours.len() > 256 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a GIF can't have more than 256 colours",
+            ));
+        }
+        Ok(colours)
+    }
+
+    fn start(&mut self) -> io::Result<()> {
+        let Some(out) = self.out.take() else {
+            return Ok(());
+        };
+        let (width, height) = self.canvas;
+        let (Ok(gif_width), Ok(gif_height)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(too_big(width, height));
+        };
+        let colours = self.colours()?;
+        let mut encoder = gif::Encoder::new(out, gif_width, gif_height, colours.as_flattened())
+            .map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        self.indices = (0..=u8::MAX)
+            .zip(colours)
+            .map(|(index, rgb)| (rgb, index))
+            .collect();
+        self.encoder = Some(encoder);
+        Ok(())
+    }
+}
+
+impl<W: Write> Renderer for Gif<W> {
+    fn render(&mut self, frame: &Frame) -> io::Result<ControlFlow<()>> {
+        let (width, height) = self.canvas;
+        if width == 0 || height == 0 {
+            return Ok(ControlFlow::Continue(()));
+        }
+        if self.encoder.is_none() {
+            self.start()?;
+        }
+        let image = paint(&frame.cells, self.cell_size, self.canvas, 0, |cell| {
+            self.indices[&self.palette.colour(cell)]
+        });
+
+        let gif_frame = gif::Frame {
+            width: width as u16,
+            height: height as u16,
+            delay: self.delay,
+            buffer: Cow::Owned(image),
+            ..gif::Frame::default()
+        };
+        let encoder = self.encoder.as_mut().expect("the encoder was just started");
+        encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+        Ok(ControlFlow::Continue(()))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        match self.encoder.take() {
+            Some(encoder) => encoder.into_inner().map_err(io::Error::other)?.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(width: usize, height: usize) -> Frame {
+        Frame::new(&Grid::new(width, height, '#')).highlight((0, 0), Colour::Red)
+    }
+
+    #[test]
+    fn palettes_take_overrides() {
+        let palette: Palette = "#=102030,red=ff0000,==000001,background=000000"
+            .parse()
+            .unwrap();
+        assert_eq!(palette.colour(Cell::from('#')), [0x10, 0x20, 0x30]);
+        assert_eq!(palette.colour(Cell::from('=')), [0, 0, 1]);
+        assert_eq!(palette.colour(Cell::new('#', Colour::Red)), [0xff, 0, 0]);
+        assert_eq!(palette.colour(Cell::from('?')), palette.foreground);
+        assert_eq!(palette.background, [0, 0, 0]);
+
+        assert!("#=12345".parse::<Palette>().is_err());
+        assert!("sand=123456".parse::<Palette>().is_err());
+    }
+
+    #[test]
+    fn images_are_sized_by_cell() {
+        let dir = std::env::temp_dir().join(format!("aoc-png-{}", std::process::id()));
+        let mut pngs = PngSequence::new(&dir, Palette::default(), 3).unwrap();
+        assert!(pngs.render(&frame(2, 1)).unwrap().is_continue());
+        let decoder = png::Decoder::new(File::open(dir.join("frame-00001.png")).unwrap());
+        let info = decoder.read_info().unwrap().info().clone();
+        assert_eq!((info.width, info.height), (6, 3));
+        fs::remove_dir_all(dir).unwrap();
+
+        let sizes = [(0, 0), (3, 2), (1, 1), (5, 5)];
+        let mut measure = Measure::default();
+        for (width, height) in sizes {
+            assert!(measure.render(&frame(width, height)).unwrap().is_continue());
+        }
+        assert_eq!(measure.size, (5, 5));
+
+        let mut out = vec![];
+        let delay = Duration::from_millis(50);
+        let mut gif = Gif::new(&mut out, Palette::default(), 2, delay, measure.size);
+        for (width, height) in sizes {
+            assert!(gif.render(&frame(width, height)).unwrap().is_continue());
+        }
+        gif.finish().unwrap();
+        drop(gif);
+        let mut decoder = gif::DecodeOptions::new().read_info(&out[..]).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (10, 10));
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 5);
+            frames += 1;
+        }
+        assert_eq!(frames, 4);
+    }
+}
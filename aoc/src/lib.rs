@@ -0,0 +1,48 @@
+use aoc_common::normalize;
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+pub mod alloc;
+pub mod answers;
+pub mod bench;
+pub mod compare;
+pub mod dashboard;
+pub mod image;
+pub mod registry;
+pub mod scaffold;
+pub mod trace;
+pub mod verify;
+pub mod viz;
+
+pub use answers::{answers_path, Answers};
+pub use registry::{find, Solver, SOLVERS};
+
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static GLOBAL: alloc::Counting = alloc::Counting;
+
+/// The `input.txt` checked in next to a day's crate.
+pub fn input_path(year: u16, day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(year.to_string())
+        .join("rust")
+        .join(format!("day-{day:02}"))
+        .join("input.txt")
+}
+
+/// Reads puzzle input from a file, or from stdin when the path is `-`, and
+/// [`normalize`]s it.
+pub fn read_input(path: &Path) -> io::Result<String> {
+    let input = if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        fs::read_to_string(path)?
+    };
+    Ok(normalize(&input).into_owned())
+}
@@ -0,0 +1,525 @@
+use aoc::{
+    alloc::{self, Usage},
+    answers_path, bench, compare,
+    dashboard::{self, Status},
+    image::{Gif, Measure, Palette, PngSequence},
+    input_path, read_input, scaffold, trace, verify, viz, Answers,
+};
+use aoc_common::{
+    params::parse_pair,
+    viz::{Renderer, TextLog},
+    Part, RawParams, Viz,
+};
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use crossterm::style::{StyledContent, Stylize};
+use std::{
+    fs::File,
+    io::{self, BufWriter, IsTerminal},
+    panic,
+    path::PathBuf,
+    process::ExitCode,
+    time::Duration,
+};
+use tracing::level_filters::LevelFilter;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code solutions")]
+struct Cli {
+    /// Log parsing and solving to stderr at this level: error, warn, info,
+    /// debug or trace
+    #[arg(long, global = true, value_name = "LEVEL")]
+    trace: Option<LevelFilter>,
+    /// Also write the log as JSON lines to this file
+    #[arg(long, global = true, value_name = "FILE", requires = "trace")]
+    trace_json: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solution for a single day, or every day of a year
+    Run(Box<RunArgs>),
+    /// Check every registered day against the stored answers
+    Verify(VerifyArgs),
+    /// Time parsing and each solved part of every registered day
+    Bench(BenchArgs),
+    /// Generate a new day crate from the template and register it
+    New(NewArgs),
+    /// List every registered day
+    List,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[arg(long)]
+    year: u16,
+    #[arg(long, required_unless_present = "all")]
+    day: Option<u8>,
+    /// Run every day of the year at once and print a summary
+    #[arg(long, conflicts_with_all = ["day", "input", "params", "viz_output"])]
+    all: bool,
+    /// With --all, how long each part may run before it is given up on
+    #[arg(long, value_name = "MS", default_value_t = 10_000, requires = "all")]
+    deadline_ms: u64,
+    /// Only run this part, both parts are run otherwise
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Puzzle input file, `-` to read stdin, or a directory of inputs to run
+    /// in parallel and compare [default: the day's input.txt]
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Extra parameter for the solver as `key=value`, may be repeated
+    #[arg(short, long = "param", value_parser = parse_pair)]
+    params: Vec<(String, String)>,
+    #[command(flatten)]
+    viz: VizArgs,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("viz_output").multiple(false)))]
+struct VizArgs {
+    /// Play the day's simulation in the terminal, for days that have one
+    #[arg(long, group = "viz_output")]
+    viz: bool,
+    /// Write the simulation's frames to a text file instead
+    #[arg(long, value_name = "FILE", group = "viz_output")]
+    viz_log: Option<PathBuf>,
+    /// Write the simulation as an animated GIF
+    #[arg(long, value_name = "FILE", group = "viz_output")]
+    viz_gif: Option<PathBuf>,
+    /// Write each frame of the simulation as a PNG into a directory
+    #[arg(long, value_name = "DIR", group = "viz_output")]
+    viz_png: Option<PathBuf>,
+    /// Only show every Nth frame
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    viz_every: usize,
+    /// Milliseconds between frames while playing or in a GIF
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    viz_delay: u64,
+    /// Start paused, to step through frames one at a time
+    #[arg(long)]
+    viz_paused: bool,
+    /// Pixels per cell in images
+    #[arg(long, value_name = "PX", default_value_t = 4)]
+    viz_cell: usize,
+    /// Colours for images as `key=rrggbb` pairs, where a key is a glyph,
+    /// highlight colour, `background` or `foreground`, like `o=c2b280,red=ff0000`
+    #[arg(long, value_name = "SPEC")]
+    viz_palette: Option<Palette>,
+}
+
+impl VizArgs {
+    fn is_enabled(&self) -> bool {
+        self.viz || self.viz_log.is_some() || self.viz_gif.is_some() || self.viz_png.is_some()
+    }
+
+    /// The renderer the flags ask for. A GIF is sized to fit every frame, so
+    /// `measure` runs the simulation once beforehand to find the biggest.
+    fn renderer(
+        &self,
+        measure: impl FnOnce() -> (usize, usize),
+    ) -> Result<Option<Box<dyn Renderer>>, String> {
+        let delay = Duration::from_millis(self.viz_delay);
+        let palette = self.viz_palette.clone().unwrap_or_default();
+        let create = |path: &PathBuf| {
+            File::create(path)
+                .map(BufWriter::new)
+                .map_err(|err| format!("{}: {err}", path.display()))
+        };
+        if let Some(path) = &self.viz_log {
+            return Ok(Some(Box::new(TextLog::new(create(path)?))));
+        }
+        if let Some(path) = &self.viz_gif {
+            let gif = Gif::new(create(path)?, palette, self.viz_cell, delay, measure());
+            return Ok(Some(Box::new(gif)));
+        }
+        if let Some(dir) = &self.viz_png {
+            let pngs = PngSequence::new(dir, palette, self.viz_cell)
+                .map_err(|err| format!("{}: {err}", dir.display()))?;
+            return Ok(Some(Box::new(pngs)));
+        }
+        if self.viz {
+            let terminal = viz::Terminal::new(delay, self.viz_paused)
+                .map_err(|err| format!("terminal: {err}"))?;
+            return Ok(Some(Box::new(terminal)));
+        }
+        Ok(None)
+    }
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Only check this year
+    #[arg(long)]
+    year: Option<u16>,
+    /// Only check this day
+    #[arg(long)]
+    day: Option<u8>,
+    /// Answers file [default: answers.toml at the root of the repository]
+    #[arg(long)]
+    answers: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Only time this year
+    #[arg(long)]
+    year: Option<u16>,
+    /// Only time this day
+    #[arg(long)]
+    day: Option<u8>,
+    /// How long to keep repeating each measurement, in milliseconds
+    #[arg(long, default_value_t = 500)]
+    budget_ms: u64,
+}
+
+#[derive(Args)]
+struct NewArgs {
+    #[arg(long)]
+    year: u16,
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+}
+
+fn run(args: RunArgs) -> Result<(), String> {
+    let parts = match args.part {
+        Some(part) => vec![Part::try_from(part).expect("part is validated by clap")],
+        None => Part::BOTH.to_vec(),
+    };
+    let Some(day) = args.day else {
+        return run_all(args.year, &parts, Duration::from_millis(args.deadline_ms));
+    };
+    let solver = aoc::find(args.year, day)
+        .ok_or_else(|| format!("no solution registered for {} day {day}", args.year))?;
+    let path = args.input.unwrap_or_else(|| input_path(args.year, day));
+    let params: RawParams = args.params.into_iter().collect();
+
+    if path.is_dir() {
+        if args.viz.is_enabled() {
+            return Err("can't visualize a directory of inputs".to_string());
+        }
+        let inputs =
+            compare::read_inputs(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        if inputs.is_empty() {
+            return Err(format!("{} has no inputs", path.display()));
+        }
+        return compare(compare::run(solver, &inputs, &params, &parts), &parts);
+    }
+
+    let input = read_input(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+    let prepared = (solver.prepare)(&input, &params).map_err(|err| err.to_string())?;
+    let measure = || {
+        let mut measure = Measure::default();
+        for &part in &parts {
+            let mut viz = Viz::new(&mut measure).every(args.viz.viz_every);
+            let _ = prepared.visualize(part, &mut viz);
+            let _ = viz.finish();
+        }
+        measure.size
+    };
+    let Some(mut renderer) = args.viz.renderer(measure)? else {
+        for part in parts {
+            let answer = prepared
+                .part(part)
+                .map_err(|err| format!("part {part}: {err}"))?;
+            println!("{answer}");
+        }
+        return Ok(());
+    };
+
+    // Answers wait until the renderer is done with the terminal.
+    let mut answers = vec![];
+    for part in parts {
+        let mut viz = Viz::new(&mut *renderer).every(args.viz.viz_every);
+        let answer = prepared.visualize(part, &mut viz);
+        let frames = viz.finish().map_err(|err| format!("viz: {err}"))?;
+        answers.push((part, answer, frames));
+    }
+    renderer.finish().map_err(|err| format!("viz: {err}"))?;
+    drop(renderer);
+
+    for (part, answer, frames) in answers {
+        if frames == 0 {
+            eprintln!(
+                "{} day {day:02} part {part} has nothing to visualize",
+                args.year
+            );
+        }
+        println!("{}", answer.map_err(|err| format!("part {part}: {err}"))?);
+    }
+    Ok(())
+}
+
+/// An answer as it fits in a table. Multi-line answers, like letters drawn on
+/// a screen, are only counted.
+fn cell(answer: &str) -> String {
+    match answer.lines().count() {
+        0 | 1 => answer.to_string(),
+        lines => format!("({lines} lines)"),
+    }
+}
+
+/// Prints a row of answers and timings per input, failing if any errored.
+fn compare(runs: Vec<compare::Run>, parts: &[Part]) -> Result<(), String> {
+    let name_width = runs.iter().map(|run| run.name.len()).max().unwrap_or(0);
+    let answer_width = runs
+        .iter()
+        .filter_map(|run| run.parts.as_ref().ok())
+        .flatten()
+        .filter_map(|part| part.answer.as_deref().ok())
+        .map(|answer| cell(answer).len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    print!("{:<name_width$}  {:>10}", "input", "parse");
+    for part in parts {
+        print!(
+            "  {:<answer_width$}  {:>10}",
+            format!("part {part}"),
+            "time"
+        );
+    }
+    println!();
+
+    for run in &runs {
+        print!(
+            "{:<name_width$}  {:>10}",
+            run.name,
+            format!("{:.2?}", run.parse)
+        );
+        match &run.parts {
+            Ok(parts) => {
+                let mut errors = vec![];
+                for part in parts {
+                    let shown = match &part.answer {
+                        Ok(answer) => cell(answer),
+                        Err(err) => {
+                            errors.push(format!("part {}: {err}", part.part));
+                            "ERROR".to_string()
+                        }
+                    };
+                    let elapsed = format!("{:.2?}", part.elapsed);
+                    print!("  {shown:<answer_width$}  {elapsed:>10}");
+                }
+                if errors.is_empty() {
+                    println!();
+                } else {
+                    println!("  {}", errors.join(", "));
+                }
+            }
+            Err(err) => println!("  ERROR {err}"),
+        }
+    }
+
+    let failed = runs.iter().filter(|run| run.failed()).count();
+    if failed > 0 {
+        return Err(format!("{failed} of {} inputs failed", runs.len()));
+    }
+    Ok(())
+}
+
+/// Runs every registered day of `year` at once and prints how each part went,
+/// failing if any errored or panicked other than in a `todo!()`.
+fn run_all(year: u16, parts: &[Part], deadline: Duration) -> Result<(), String> {
+    let days: Vec<_> = aoc::SOLVERS
+        .iter()
+        .filter(|solver| solver.year == year)
+        .map(|solver| {
+            let path = input_path(solver.year, solver.day);
+            let input = read_input(&path).map_err(|err| format!("{}: {err}", path.display()));
+            dashboard::Day { solver, input }
+        })
+        .collect();
+    if days.is_empty() {
+        return Err(format!("no solutions registered for {year}"));
+    }
+
+    // panics are reported in the summary, and a stuck part can still panic
+    // in the background after it
+    panic::set_hook(Box::new(|_| {}));
+    let outcomes = dashboard::run(days, parts, deadline);
+
+    let colour = io::stdout().is_terminal();
+    println!(
+        "{:<12} {:>4}  {:<7}  {:>10}  answer",
+        "day", "part", "status", "time"
+    );
+    let mut counts = [0; 5];
+    for outcome in &outcomes {
+        let (index, paint): (usize, fn(String) -> StyledContent<String>) = match outcome.status {
+            Status::Ok(_) => (0, Stylize::green),
+            Status::Todo => (1, Stylize::yellow),
+            Status::Timeout => (2, Stylize::magenta),
+            Status::Panic(_) => (3, Stylize::red),
+            Status::Error(_) => (4, Stylize::red),
+        };
+        counts[index] += 1;
+        let status = format!("{:<7}", outcome.status);
+        let status = if colour {
+            paint(status).to_string()
+        } else {
+            status
+        };
+        let time = match outcome.status {
+            Status::Todo => "-".to_string(),
+            Status::Timeout => format!(">{:.2?}", outcome.elapsed),
+            _ => format!("{:.2?}", outcome.elapsed),
+        };
+        let answer = match &outcome.status {
+            Status::Ok(answer) => cell(answer),
+            Status::Error(message) | Status::Panic(message) => message.clone(),
+            Status::Todo | Status::Timeout => String::new(),
+        };
+        let day = format!("{} day {:02}", outcome.year, outcome.day);
+        let part = outcome.part.to_string();
+        let row = format!("{day:<12} {part:>4}  {status}  {time:>10}  {answer}");
+        println!("{}", row.trim_end());
+    }
+
+    let [ok, todo, timeout, panicked, errors] = counts;
+    println!("{ok} ok, {todo} todo, {timeout} timeout, {panicked} panic, {errors} error");
+    if panicked + errors > 0 {
+        return Err(format!(
+            "{} of {} parts failed",
+            panicked + errors,
+            outcomes.len()
+        ));
+    }
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<(), String> {
+    let answers = Answers::load(&args.answers.unwrap_or_else(answers_path))?;
+    let solvers = aoc::SOLVERS.iter().filter(|solver| {
+        args.year.is_none_or(|year| solver.year == year)
+            && args.day.is_none_or(|day| solver.day == day)
+    });
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for solver in solvers {
+        let path = input_path(solver.year, solver.day);
+        let input = read_input(&path).map_err(|err| format!("{}: {err}", path.display()));
+        for check in verify::check(solver, input.as_deref().map_err(Clone::clone), &answers) {
+            match check.status {
+                verify::Status::Pass => passed += 1,
+                verify::Status::Missing => missing += 1,
+                _ => failed += 1,
+            }
+            if check.status == verify::Status::Missing {
+                println!(
+                    "{} day {:02} part {}  {}",
+                    check.year, check.day, check.part, check.status
+                );
+            } else {
+                println!(
+                    "{} day {:02} part {}  {}  (parse {:.2?}, solve {:.2?})",
+                    check.year, check.day, check.part, check.status, check.parse, check.solve
+                );
+            }
+        }
+    }
+
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    if failed > 0 {
+        return Err(format!("{failed} stored answers no longer match"));
+    }
+    Ok(())
+}
+
+fn bench(args: BenchArgs) -> Result<(), String> {
+    let answers = Answers::load(&answers_path())?;
+    let budget = Duration::from_millis(args.budget_ms);
+    let solvers = aoc::SOLVERS.iter().filter(|solver| {
+        args.year.is_none_or(|year| solver.year == year)
+            && args.day.is_none_or(|day| solver.day == day)
+    });
+
+    println!(
+        "{:<12} {:<8} {:>12} {:>12} {:>12}",
+        "day", "input", "parse", "part 1", "part 2"
+    );
+    for solver in solvers {
+        let parts = bench::solved_parts(solver, &answers);
+        if parts.is_empty() {
+            continue;
+        }
+        let (input, source) = bench::bench_input(solver);
+        let day = format!("{} day {:02}", solver.year, solver.day);
+        match bench::time(solver, &input, &parts, budget) {
+            Ok(timings) => {
+                let [part1, part2] = Part::BOTH.map(|part| {
+                    timings
+                        .part(part)
+                        .map_or("-".to_string(), |elapsed| format!("{elapsed:.2?}"))
+                });
+                let parse = format!("{:.2?}", timings.parse);
+                println!("{day:<12} {source:<8} {parse:>12} {part1:>12} {part2:>12}");
+                if let Some(allocations) = &timings.allocations {
+                    print_usage(allocations);
+                }
+            }
+            Err(err) => println!("{day:<12} {source:<8} error: {err}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Rows of allocation counts under a day's timings, lined up with its phases.
+fn print_usage(allocations: &bench::Allocations) {
+    for label in ["allocs", "bytes", "peak"] {
+        let show = |usage: Usage| match label {
+            "allocs" => usage.allocations.to_string(),
+            "bytes" => alloc::human_bytes(usage.bytes),
+            _ => alloc::human_bytes(usage.peak),
+        };
+        let parse = show(allocations.parse);
+        let [part1, part2] =
+            Part::BOTH.map(|part| allocations.part(part).map_or("-".to_string(), show));
+        println!("{:<12} {label:<8} {parse:>12} {part1:>12} {part2:>12}", "");
+    }
+}
+
+fn new(args: NewArgs) -> Result<(), String> {
+    let dir = scaffold::new_day(&scaffold::repo_root(), args.year, args.day)?;
+    println!("created {}", dir.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(level) = cli.trace {
+        let json = cli
+            .trace_json
+            .as_ref()
+            .map(|path| File::create(path).map_err(|err| format!("{}: {err}", path.display())))
+            .transpose();
+        if let Err(err) = json.and_then(|json| trace::init(level, json)) {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let result = match cli.command {
+        Command::Run(args) => run(*args),
+        Command::Verify(args) => verify(args),
+        Command::Bench(args) => bench(args),
+        Command::New(args) => new(args),
+        Command::List => {
+            for solver in aoc::SOLVERS {
+                println!("{} day {:02}", solver.year, solver.day);
+            }
+            Ok(())
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use aoc_common::{
+    solution::{prepare, Prepared},
+    RawParams, Result, Solution,
+};
+
+pub type PrepareFn = for<'a> fn(&'a str, &RawParams) -> Result<Box<dyn Prepared + 'a>>;
+
+/// A day whose solutions can be run by the `aoc` binary.
+pub struct Solver {
+    pub year: u16,
+    pub day: u8,
+    pub example: &'static str,
+    pub prepare: PrepareFn,
+}
+
+impl Solver {
+    pub const fn of<S: Solution + 'static>() -> Self {
+        Self {
+            year: S::YEAR,
+            day: S::DAY,
+            example: S::EXAMPLE,
+            prepare: prepare::<S>,
+        }
+    }
+}
+
+macro_rules! register {
+    ($($solution:path),* $(,)?) => {
+        &[$(Solver::of::<$solution>()),*]
+    };
+}
+
+pub static SOLVERS: &[Solver] = register![
+    aoc_2022_day_01::Day01,
+    aoc_2022_day_02::Day02,
+    aoc_2022_day_03::Day03,
+    aoc_2022_day_04::Day04,
+    aoc_2022_day_05::Day05,
+    aoc_2022_day_06::Day06,
+    aoc_2022_day_07::Day07,
+    aoc_2022_day_08::Day08,
+    aoc_2022_day_09::Day09,
+    aoc_2022_day_10::Day10,
+    aoc_2022_day_11::Day11,
+    aoc_2022_day_12::Day12,
+    aoc_2022_day_13::Day13,
+    aoc_2022_day_14::Day14,
+    aoc_2022_day_15::Day15,
+    aoc_2022_day_16::Day16,
+    aoc_2022_day_17::Day17,
+    aoc_2022_day_18::Day18,
+    aoc_2023_day_01::Day01,
+    aoc_2023_day_02::Day02,
+    aoc_2023_day_03::Day03,
+    aoc_2023_day_04::Day04,
+    aoc_2023_day_05::Day05,
+    aoc_2023_day_06::Day06,
+    aoc_2023_day_07::Day07,
+    aoc_2023_day_08::Day08,
+    aoc_2023_day_09::Day09,
+    aoc_2023_day_10::Day10,
+    aoc_2023_day_11::Day11,
+    aoc_2023_day_12::Day12,
+    aoc_2023_day_13::Day13,
+    aoc_2023_day_14::Day14,
+    aoc_2023_day_15::Day15,
+    aoc_2023_day_16::Day16,
+    aoc_2023_day_17::Day17,
+    aoc_2023_day_18::Day18,
+    aoc_2023_day_19::Day19,
+    aoc_2023_day_20::Day20,
+    aoc_2023_day_21::Day21,
+    aoc_2023_day_22::Day22,
+    aoc_2023_day_23::Day23,
+    aoc_2023_day_24::Day24,
+    aoc_2023_day_25::Day25,
+];
+
+pub fn find(year: u16, day: u8) -> Option<&'static Solver> {
+    SOLVERS
+        .iter()
+        .find(|solver| solver.year == year && solver.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{answers_path, bench::solved_parts, Answers};
+    use aoc_common::normalize;
+    use std::collections::HashSet;
+
+    #[test]
+    fn days_are_registered_once() {
+        let mut seen = HashSet::new();
+        for solver in SOLVERS {
+            assert!(seen.insert((solver.year, solver.day)));
+        }
+        assert!(find(2022, 15).is_some());
+        assert!(find(2022, 26).is_none());
+    }
+
+    #[test]
+    fn examples_solve_the_same_with_any_line_endings() {
+        let answers = Answers::load(&answers_path()).unwrap();
+        let run = |solver: &Solver, input: &str| {
+            let input = normalize(input);
+            let prepared = (solver.prepare)(&input, &RawParams::new())?;
+            solved_parts(solver, &answers)
+                .into_iter()
+                .map(|part| prepared.part(part))
+                .collect::<Result<Vec<_>>>()
+        };
+
+        for solver in SOLVERS {
+            let day = format!("{} day {:02}", solver.year, solver.day);
+            let expected = run(solver, solver.example).unwrap_or_else(|err| panic!("{day}: {err}"));
+            let windows = format!("\u{feff}{}\r\n\r\n", solver.example.replace('\n', "\r\n"));
+            assert_eq!(run(solver, &windows), Ok(expected), "{day}");
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const CARGO_TEMPLATE: &str = r#"[package]
+name = "aoc-{YEAR}-day-{NN}"
+version.workspace = true
+edition.workspace = true
+
+[dependencies]
+aoc-common.workspace = true
+"#;
+
+const LIB_TEMPLATE: &str = r#"use aoc_common::{normalize, Result, Solution};
+
+pub struct Day{NN};
+
+impl Solution for Day{NN} {
+    const YEAR: u16 = {YEAR};
+    const DAY: u8 = {DAY};
+    const EXAMPLE: &'static str = "";
+
+    type Parsed<'a> = &'a str;
+    type Params = ();
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>> {
+        Ok(input)
+    }
+
+    fn part1(_input: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!()
+    }
+
+    fn part2(_input: &Self::Parsed<'_>, _params: &()) -> Result<String> {
+        todo!()
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day{NN}::part1(&Day{NN}::parse(&input)?, &())
+}
+
+pub fn process_part2(input: &str) -> Result<String> {
+    let input = normalize(input);
+    Day{NN}::part2(&Day{NN}::parse(&input)?, &())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_common::example_tests!(Day{NN});
+}
+"#;
+
+/// The root of the repository, which holds the workspace `Cargo.toml`.
+pub fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// Generates the `YEAR/rust/day-NN` crate under `root` from the template and
+/// registers it with the workspace and the runner.
+///
+/// Nothing is written if the day already exists or is already registered.
+pub fn new_day(root: &Path, year: u16, day: u8) -> Result<PathBuf, String> {
+    let dir = root
+        .join(year.to_string())
+        .join("rust")
+        .join(format!("day-{day:02}"));
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+
+    let fill = |template: &str| {
+        template
+            .replace("{YEAR}", &year.to_string())
+            .replace("{DAY}", &day.to_string())
+            .replace("{NN}", &format!("{day:02}"))
+    };
+    let workspace_path = root.join("Cargo.toml");
+    let runner_path = root.join("aoc").join("Cargo.toml");
+    let registry_path = root.join("aoc").join("src").join("registry.rs");
+
+    let workspace = read(&workspace_path)?;
+    let workspace = if workspace.contains(&format!("\"{year}/rust/day-*\"")) {
+        workspace
+    } else {
+        insert_sorted(
+            &workspace,
+            |line| line.starts_with("    \"") && line.ends_with("/rust/day-*\","),
+            &format!("    \"{year}/rust/day-*\","),
+        )?
+    };
+    let runner = insert_sorted(
+        &read(&runner_path)?,
+        |line| line.starts_with("aoc-") && line.contains("-day-"),
+        &fill(r#"aoc-{YEAR}-day-{NN} = { path = "../{YEAR}/rust/day-{NN}" }"#),
+    )?;
+    let registry = insert_sorted(
+        &read(&registry_path)?,
+        |line| line.starts_with("    aoc_") && line.contains("_day_"),
+        &fill("    aoc_{YEAR}_day_{NN}::Day{NN},"),
+    )?;
+
+    fs::create_dir_all(dir.join("src")).map_err(|err| format!("{}: {err}", dir.display()))?;
+    write(&dir.join("Cargo.toml"), &fill(CARGO_TEMPLATE))?;
+    write(&dir.join("src").join("lib.rs"), &fill(LIB_TEMPLATE))?;
+    write(&dir.join("input.txt"), "")?;
+    write(&workspace_path, &workspace)?;
+    write(&runner_path, &runner)?;
+    write(&registry_path, &registry)?;
+
+    Ok(dir)
+}
+
+/// Inserts `entry` as a line among the contiguous lines matching `is_entry`,
+/// keeping them sorted.
+fn insert_sorted(
+    text: &str,
+    is_entry: impl Fn(&str) -> bool,
+    entry: &str,
+) -> Result<String, String> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let entries: Vec<usize> = (0..lines.len()).filter(|&i| is_entry(lines[i])).collect();
+    let (Some(&first), Some(&last)) = (entries.first(), entries.last()) else {
+        return Err(format!("nowhere to register `{}`", entry.trim()));
+    };
+    if entries.iter().any(|&i| lines[i] == entry) {
+        return Err(format!("`{}` is already registered", entry.trim()));
+    }
+
+    let at = (first..=last)
+        .find(|&i| is_entry(lines[i]) && lines[i] > entry)
+        .unwrap_or(last + 1);
+    lines.insert(at, entry);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("aoc").join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"2022/rust/day-*\",\n]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("aoc").join("Cargo.toml"),
+            "[dependencies]\nclap.workspace = true\naoc-2022-day-01 = { path = \"../2022/rust/day-01\" }\naoc-2022-day-03 = { path = \"../2022/rust/day-03\" }\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("aoc").join("src").join("registry.rs"),
+            "pub static SOLVERS: &[Solver] = register![\n    aoc_2022_day_01::Day01,\n    aoc_2022_day_03::Day03,\n];\n",
+        )
+        .unwrap();
+        root
+    }
+
+    #[test]
+    fn generates_and_registers_a_day() {
+        let root = fixture("generate");
+        new_day(&root, 2022, 2).unwrap();
+        new_day(&root, 2024, 1).unwrap();
+
+        let lib = read(&root.join("2022/rust/day-02/src/lib.rs")).unwrap();
+        assert!(lib.contains("pub struct Day02;"));
+        assert!(lib.contains("const YEAR: u16 = 2022;\n    const DAY: u8 = 2;"));
+        assert!(lib.contains("aoc_common::example_tests!(Day02);"));
+        assert!(!root.join("2022/rust/day-02/examples").exists());
+        assert!(read(&root.join("2024/rust/day-01/Cargo.toml"))
+            .unwrap()
+            .contains("name = \"aoc-2024-day-01\""));
+        assert_eq!(
+            read(&root.join("aoc/src/registry.rs")).unwrap(),
+            "pub static SOLVERS: &[Solver] = register![\n    aoc_2022_day_01::Day01,\n    aoc_2022_day_02::Day02,\n    aoc_2022_day_03::Day03,\n    aoc_2024_day_01::Day01,\n];\n"
+        );
+        assert!(read(&root.join("Cargo.toml"))
+            .unwrap()
+            .contains("    \"2022/rust/day-*\",\n    \"2024/rust/day-*\",\n]"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_overwrite_an_existing_day() {
+        let root = fixture("overwrite");
+        new_day(&root, 2022, 2).unwrap();
+        let lib = root.join("2022/rust/day-02/src/lib.rs");
+        fs::write(&lib, "solved").unwrap();
+
+        let err = new_day(&root, 2022, 2).unwrap_err();
+        assert!(err.ends_with("already exists"), "{err}");
+        assert_eq!(read(&lib).unwrap(), "solved");
+
+        let err = new_day(&root, 2022, 3).unwrap_err();
+        assert_eq!(
+            err,
+            "`aoc-2022-day-03 = { path = \"../2022/rust/day-03\" }` is already registered"
+        );
+        assert!(!root.join("2022/rust/day-03").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+//! Logging for `--trace`, on top of the spans `aoc_common` opens around
+//! parsing and solving.
+
+use std::{
+    fs::File,
+    io::{self, IsTerminal},
+    sync::Mutex,
+};
+use tracing::{level_filters::LevelFilter, Subscriber};
+use tracing_subscriber::{
+    fmt::{format::FmtSpan, MakeWriter},
+    layer::SubscriberExt,
+    Layer,
+};
+
+/// Logs spans and events at `level` and above as text to `text`, coloured
+/// when `ansi` is set, and as JSON lines to `json` when given.
+///
+/// Spans are logged as they close, so each `parse` and `solve` line says how
+/// long it took.
+pub fn subscriber<T, W>(
+    level: LevelFilter,
+    text: T,
+    ansi: bool,
+    json: Option<W>,
+) -> impl Subscriber + Send + Sync
+where
+    T: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let text = tracing_subscriber::fmt::layer()
+        .with_writer(text)
+        .with_ansi(ansi)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_filter(level);
+    let json = json.map(|writer| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(writer)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_filter(level)
+    });
+    tracing_subscriber::registry().with(text).with(json)
+}
+
+/// Logs to stderr, and to `json` when given, for the rest of the run.
+pub fn init(level: LevelFilter, json: Option<File>) -> Result<(), String> {
+    let ansi = io::stderr().is_terminal();
+    tracing::subscriber::set_global_default(subscriber(
+        level,
+        io::stderr,
+        ansi,
+        json.map(Mutex::new),
+    ))
+    .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{solution::prepare, Part, RawParams};
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn json_lines(level: LevelFilter, input: &str) -> Vec<String> {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = subscriber(level, io::sink, false, Some(move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let prepared = prepare::<aoc_2022_day_01::Day01>(input, &RawParams::new());
+            if let Ok(prepared) = prepared {
+                let _ = prepared.part(Part::One);
+            }
+        });
+        let log = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        log.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn logs_parse_and_solve_spans_as_json() {
+        let lines = json_lines(LevelFilter::DEBUG, "1000\n2000\n\n3000");
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains(r#""message":"close""#));
+        assert!(lines[0].contains(r#""name":"parse""#));
+        assert!(lines[1].contains(r#""answer":"3000""#));
+        assert!(lines[1].contains(r#""part":"1""#));
+        assert!(lines[2].contains(r#""name":"solve""#));
+        assert!(lines[2].contains(r#""time.busy""#));
+    }
+
+    #[test]
+    fn level_filters_events() {
+        let lines = json_lines(LevelFilter::WARN, "1000\nx");
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains(r#""level":"WARN""#));
+        assert!(lines[0].contains("expected"));
+        assert!(json_lines(LevelFilter::OFF, "1000\nx").is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{answers::Answers, Solver};
+use aoc_common::{Part, RawParams};
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// The solver returned an error or panicked.
+    Error(String),
+    /// There is no stored answer, so the part was not run.
+    Missing,
+}
+
+impl Status {
+    pub fn is_regression(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => {
+                write!(f, "FAIL expected {expected:?}, got {actual:?}")
+            }
+            Status::Error(err) => write!(f, "ERROR {err}"),
+            Status::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+/// The outcome of checking one part of one day.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub status: Status,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Runs every part of `solver` that has a stored answer against `input`.
+///
+/// Parts without an answer are reported as [`Status::Missing`] and never run,
+/// which keeps unfinished days that `todo!()` or never return out of the way.
+pub fn check(solver: &Solver, input: Result<&str, String>, answers: &Answers) -> Vec<Check> {
+    let expected: Vec<(Part, Option<&str>)> = Part::BOTH
+        .iter()
+        .map(|&part| (part, answers.get(solver.year, solver.day, part)))
+        .collect();
+    let result = |part, status, parse, solve| Check {
+        year: solver.year,
+        day: solver.day,
+        part,
+        status,
+        parse,
+        solve,
+    };
+
+    if expected.iter().all(|(_, answer)| answer.is_none()) {
+        return expected
+            .into_iter()
+            .map(|(part, _)| result(part, Status::Missing, Duration::ZERO, Duration::ZERO))
+            .collect();
+    }
+
+    let start = Instant::now();
+    let prepared = input.and_then(|input| {
+        catch(|| (solver.prepare)(input, &RawParams::new()))
+            .and_then(|prepared| prepared.map_err(|err| err.to_string()))
+    });
+    let parse = start.elapsed();
+
+    expected
+        .into_iter()
+        .map(|(part, answer)| {
+            let Some(expected) = answer else {
+                return result(part, Status::Missing, parse, Duration::ZERO);
+            };
+            let prepared = match &prepared {
+                Ok(prepared) => prepared,
+                Err(err) => return result(part, Status::Error(err.clone()), parse, Duration::ZERO),
+            };
+
+            let start = Instant::now();
+            let actual = catch(|| prepared.part(part))
+                .and_then(|actual| actual.map_err(|err| err.to_string()));
+            let solve = start.elapsed();
+
+            let status = match actual {
+                Ok(actual) if actual.trim_end() == expected.trim_end() => Status::Pass,
+                Ok(actual) => Status::Fail {
+                    expected: expected.to_string(),
+                    actual,
+                },
+                Err(err) => Status::Error(err),
+            };
+            result(part, status, parse, solve)
+        })
+        .collect()
+}
+
+pub(crate) fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(&*payload))
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    format!("panicked: {}", payload_message(payload))
+}
+
+/// What a panic was raised with, for the usual string payloads.
+pub(crate) fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    fn solver() -> &'static Solver {
+        crate::find(2022, 1).unwrap()
+    }
+
+    #[test]
+    fn reports_pass_fail_and_missing() {
+        let answers = Answers::parse("[2022.01]\npart1 = 24000\npart2 = 1").unwrap();
+        let checks = check(solver(), Ok(INPUT), &answers);
+        assert_eq!(checks[0].status, Status::Pass);
+        assert_eq!(
+            checks[1].status,
+            Status::Fail {
+                expected: "1".to_string(),
+                actual: "45000".to_string()
+            }
+        );
+
+        let checks = check(solver(), Ok(INPUT), &Answers::default());
+        assert!(checks.iter().all(|check| check.status == Status::Missing));
+    }
+
+    #[test]
+    fn parse_errors_are_regressions() {
+        let answers = Answers::parse("[2022.01]\npart1 = 24000").unwrap();
+        let checks = check(solver(), Ok("1000\nabc"), &answers);
+        assert!(checks[0].status.is_regression());
+        assert_eq!(checks[1].status, Status::Missing);
+
+        let checks = check(solver(), Err("no input".to_string()), &answers);
+        assert_eq!(checks[0].status, Status::Error("no input".to_string()));
+    }
+}
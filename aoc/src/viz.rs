@@ -0,0 +1,140 @@
+use aoc_common::viz::{Frame, Renderer};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+use std::{
+    io::{self, Stdout, Write},
+    ops::ControlFlow,
+    time::Duration,
+};
+
+const HELP: &str = "space play/pause  n step  +/- speed  q stop";
+
+/// How far `+` and `-` change the speed. Speeding up stops short of zero,
+/// which would poll for keys in a busy loop.
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Plays frames in the terminal's alternate screen.
+///
+/// Frames advance every `delay` until paused with space, after which `n`
+/// steps one frame at a time. The screen is restored when this is dropped.
+pub struct Terminal {
+    out: Stdout,
+    delay: Duration,
+    paused: bool,
+    stopped: bool,
+    frames: usize,
+}
+
+impl Terminal {
+    pub fn new(delay: Duration, paused: bool) -> io::Result<Self> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self {
+            out,
+            delay,
+            paused,
+            stopped: false,
+            frames: 0,
+        })
+    }
+
+    /// Keeps the last frame up until a key is pressed.
+    fn wait(&mut self) -> io::Result<()> {
+        let (_, rows) = terminal::size()?;
+        execute!(
+            self.out,
+            cursor::MoveTo(0, rows.saturating_sub(1)),
+            terminal::Clear(ClearType::CurrentLine),
+            Print("done, press any key to exit"),
+        )?;
+        loop {
+            if let Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Handles keys until it's time for the next frame.
+    fn controls(&mut self) -> io::Result<ControlFlow<()>> {
+        loop {
+            if !self.paused && !event::poll(self.delay)? {
+                return Ok(ControlFlow::Continue(()));
+            }
+            let event = event::read()?;
+            let Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event
+            else {
+                continue;
+            };
+            // raw mode swallows ctrl-c, so it has to stop playback by hand
+            let interrupt = code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL);
+            match code {
+                _ if interrupt => {
+                    self.stopped = true;
+                    return Ok(ControlFlow::Break(()));
+                }
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    self.stopped = true;
+                    return Ok(ControlFlow::Break(()));
+                }
+                KeyCode::Char(' ') => self.paused = !self.paused,
+                KeyCode::Char('n') | KeyCode::Right if self.paused => {
+                    return Ok(ControlFlow::Continue(()))
+                }
+                KeyCode::Char('+') => self.delay = (self.delay / 2).max(MIN_DELAY),
+                KeyCode::Char('-') => self.delay = (self.delay * 2).clamp(MIN_DELAY, MAX_DELAY),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Renderer for Terminal {
+    fn render(&mut self, frame: &Frame) -> io::Result<ControlFlow<()>> {
+        if self.stopped {
+            return Ok(ControlFlow::Break(()));
+        }
+        self.frames += 1;
+        let (columns, rows) = terminal::size()?;
+        let status = format!("frame {}  {}  [{HELP}]", self.frames, frame.caption);
+        queue!(
+            self.out,
+            cursor::MoveTo(0, 0),
+            terminal::Clear(ClearType::All),
+            Print(frame.ansi(columns as usize, rows.saturating_sub(1) as usize)),
+            cursor::MoveTo(0, rows.saturating_sub(1)),
+            Print(status.chars().take(columns as usize).collect::<String>()),
+        )?;
+        self.out.flush()?;
+        self.controls()
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.stopped || self.frames == 0 {
+            return Ok(());
+        }
+        self.wait()
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}